@@ -0,0 +1,228 @@
+//! Protocol encoding and decoding benchmarks.
+//!
+//! Allocations are asserted before timing: reading a packet from an async stream allocates only
+//! the payload buffer on top of decoding it, and writing a packet allocates only the packet
+//! buffer, so stream traits do not box any future.
+//!
+//! Reference numbers, measured on the same machine (the first version at protocol 3):
+//!
+//! - per field async decoding and encoding, with `async_trait` streams: 3239 allocations and
+//!   125 µs to read the keyboard controller, 3022 allocations and 102 µs to write 300 LEDs;
+//! - buffered decoding and encoding, with `async_trait` streams: 162 allocations and 28-33 µs to
+//!   read the controller, 2 allocations and 13-15 µs to write LEDs;
+//! - buffered decoding and encoding, with `impl Future` streams: 159 allocations and 27-32 µs to
+//!   read the controller, 1 allocation and 13-15 µs to write LEDs.
+//!
+//! Dropping `async_trait` (and raising MSRV to 1.75) removes one boxed future per stream call,
+//! which shows in allocation counts but not measurably in timings.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::Cursor;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use bytes::BytesMut;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use openrgb::data::PacketId::{RGBControllerUpdateLeds, RequestControllerData};
+use openrgb::data::{
+    Color, ColorMode, Controller, DeviceType, Matrix, Mode, ModeFlag, OpenRGBWritable, Zone,
+    ZoneType, LED,
+};
+use openrgb::{OpenRGBCodec, OpenRGBReadableStream, OpenRGBWritableStream, DEFAULT_PROTOCOL};
+use openrgb_data::{DecodeLimits, Limited, OpenRGBReadableSync, OpenRGBWritableSync, WriteVec};
+use tokio::runtime::{Builder, Runtime};
+use tokio_util::codec::Decoder;
+
+/// Size of packet header on the wire.
+const HEADER_SIZE: usize = 16;
+
+/// Allocator counting allocations, to assert them before timings.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Count allocations performed by given function.
+fn count_allocations<T>(f: impl FnOnce() -> T) -> usize {
+    let start = ALLOCATIONS.load(Ordering::Relaxed);
+    let result = f();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - start;
+    drop(result);
+    allocations
+}
+
+/// Full size keyboard, with one LED per key on a 6x21 matrix.
+fn keyboard() -> Controller {
+    let num_leds = 6 * 21;
+    Controller {
+        r#type: DeviceType::Keyboard,
+        name: "Test Keyboard".to_string(),
+        vendor: "Test Vendor".to_string(),
+        description: "Test Description".to_string(),
+        version: "1.0".to_string(),
+        serial: "1234".to_string(),
+        location: "HID: /dev/hidraw0".to_string(),
+        active_mode: 0,
+        modes: (0..10)
+            .map(|i| Mode {
+                name: format!("Mode {}", i),
+                value: i,
+                flags: ModeFlag::HasSpeed
+                    | ModeFlag::HasBrightness
+                    | ModeFlag::HasModeSpecificColor,
+                speed_min: Some(0),
+                speed_max: Some(4),
+                brightness_min: Some(0),
+                brightness_max: Some(100),
+                colors_min: Some(1),
+                colors_max: Some(2),
+                speed: Some(2),
+                brightness: Some(100),
+                direction: None,
+                color_mode: Some(ColorMode::ModeSpecific),
+                colors: vec![Color { r: 255, g: 0, b: 0 }],
+            })
+            .collect(),
+        zones: vec![Zone {
+            name: "Keys".to_string(),
+            r#type: ZoneType::Matrix,
+            leds_min: num_leds,
+            leds_max: num_leds,
+            leds_count: num_leds,
+            matrix: Matrix::from_row_major((0..num_leds).collect(), 6, 21),
+            segments: vec![],
+        }],
+        leds: (0..num_leds)
+            .map(|i| LED {
+                name: format!("Key {}", i),
+                value: i,
+            })
+            .collect(),
+        colors: vec![Color { r: 0, g: 0, b: 255 }; num_leds as usize],
+    }
+}
+
+fn runtime() -> Runtime {
+    Builder::new_current_thread().build().unwrap()
+}
+
+fn controller_packet() -> Vec<u8> {
+    let mut packet = Vec::new();
+    WriteVec::new(&mut packet)
+        .write_packet(DEFAULT_PROTOCOL, 0, RequestControllerData, keyboard())
+        .unwrap();
+    packet
+}
+
+fn decode_controller(c: &mut Criterion) {
+    let packet = controller_packet();
+    let runtime = runtime();
+
+    let payload = &packet[HEADER_SIZE..];
+    let decode_allocations = count_allocations(|| {
+        Limited::new(payload, DecodeLimits::DEFAULT)
+            .read_bounded::<Controller>(payload.len(), DEFAULT_PROTOCOL)
+            .unwrap()
+    });
+    let mut stream = Cursor::new(packet.clone());
+    let read_allocations = count_allocations(|| {
+        runtime
+            .block_on(stream.read_packet::<Controller>(
+                DEFAULT_PROTOCOL,
+                0,
+                RequestControllerData,
+                &DecodeLimits::DEFAULT,
+            ))
+            .unwrap()
+    });
+    assert_eq!(
+        read_allocations,
+        decode_allocations + 1,
+        "reading controller packet should only allocate payload on top of decoding"
+    );
+
+    c.bench_function("read controller packet from async stream", |b| {
+        b.iter_batched(
+            || Cursor::new(packet.clone()),
+            |mut stream| {
+                runtime
+                    .block_on(stream.read_packet::<Controller>(
+                        DEFAULT_PROTOCOL,
+                        0,
+                        RequestControllerData,
+                        &DecodeLimits::DEFAULT,
+                    ))
+                    .unwrap()
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("decode controller frame", |b| {
+        b.iter_batched(
+            || BytesMut::from(&packet[..]),
+            |mut buf| {
+                let (_, payload) = OpenRGBCodec::new().decode(&mut buf).unwrap().unwrap();
+                Limited::new(&payload[..], DecodeLimits::DEFAULT)
+                    .read_bounded::<Controller>(payload.len(), DEFAULT_PROTOCOL)
+                    .unwrap()
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn encode_leds(c: &mut Criterion) {
+    let colors = vec![Color { r: 0, g: 0, b: 255 }; 300];
+    let runtime = runtime();
+
+    let mut stream = Vec::with_capacity(0x1000);
+    let write_allocations = count_allocations(|| {
+        runtime
+            .block_on(OpenRGBWritableStream::write_packet(
+                &mut stream,
+                DEFAULT_PROTOCOL,
+                0,
+                RGBControllerUpdateLeds,
+                (colors.as_slice().size(DEFAULT_PROTOCOL), colors.as_slice()),
+            ))
+            .unwrap()
+    });
+    assert_eq!(
+        write_allocations, 1,
+        "writing LEDs packet should only allocate packet buffer"
+    );
+
+    c.bench_function("write update LEDs packet to async stream", |b| {
+        b.iter_batched_ref(
+            || Vec::with_capacity(0x1000),
+            |stream: &mut Vec<u8>| {
+                runtime
+                    .block_on(OpenRGBWritableStream::write_packet(
+                        stream,
+                        DEFAULT_PROTOCOL,
+                        0,
+                        RGBControllerUpdateLeds,
+                        (colors.as_slice().size(DEFAULT_PROTOCOL), colors.as_slice()),
+                    ))
+                    .unwrap()
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+criterion_group!(benches, decode_controller, encode_leds);
+criterion_main!(benches);
@@ -0,0 +1,160 @@
+extern crate std;
+
+use core::fmt::Debug;
+use core::ops::{Deref, DerefMut};
+use std::io;
+use std::net::{Ipv4Addr, TcpStream, ToSocketAddrs};
+
+use alloc::format;
+use genio::std_impls::GenioIo;
+
+use crate::OpenRGBClient;
+use crate::OpenRGBError::{self, *};
+
+/// Default address used by [OpenRGBBlocking::connect].
+pub static DEFAULT_ADDR: (Ipv4Addr, u16) = (Ipv4Addr::LOCALHOST, 6742);
+
+/// Blocking OpenRGB client.
+///
+/// This client has the same API as the async `openrgb` crate client, over any [std::io] stream
+/// (by default [TcpStream]). See [OpenRGBClient] for available requests.
+pub struct OpenRGBBlocking<S: io::Read + io::Write = TcpStream>(OpenRGBClient<GenioIo<S>>);
+
+impl OpenRGBBlocking<TcpStream> {
+    /// Connect to default OpenRGB server.
+    ///
+    /// Use [OpenRGBBlocking::connect_to] to connect to a specific server.
+    pub fn connect() -> Result<Self, OpenRGBError> {
+        Self::connect_to(DEFAULT_ADDR)
+    }
+
+    /// Connect to OpenRGB server at given coordinates.
+    ///
+    /// Use [OpenRGBBlocking::connect] to connect to default server.
+    ///
+    /// # Arguments
+    /// * `addr` - A socket address (eg: a `(host, port)` tuple)
+    pub fn connect_to(addr: impl ToSocketAddrs + Debug) -> Result<Self, OpenRGBError> {
+        let stream = TcpStream::connect(&addr).map_err(|e| {
+            CommunicationError(format!(
+                "failed opening connection to OpenRGB server at {:?}: {}",
+                addr, e
+            ))
+        })?;
+        Self::new(stream)
+    }
+}
+
+impl<S: io::Read + io::Write> OpenRGBBlocking<S> {
+    /// Build a new client from given stream.
+    ///
+    /// This constructor expects a connected, ready to use stream.
+    pub fn new(stream: S) -> Result<Self, OpenRGBError> {
+        OpenRGBClient::new(GenioIo::new(stream)).map(Self)
+    }
+
+    /// Unwrap underlying stream.
+    pub fn into_inner(self) -> S {
+        self.0.into_inner().into_inner()
+    }
+}
+
+impl<S: io::Read + io::Write> Deref for OpenRGBBlocking<S> {
+    type Target = OpenRGBClient<GenioIo<S>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S: io::Read + io::Write> DerefMut for OpenRGBBlocking<S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Cursor, Read, Write};
+    use std::vec::Vec;
+
+    use crate::{OpenRGBBlocking, OpenRGBError, DEFAULT_PROTOCOL};
+
+    /// In-memory stream, reading from a fixed input and recording output.
+    struct MemoryStream {
+        input: Cursor<Vec<u8>>,
+        output: Vec<u8>,
+    }
+
+    impl MemoryStream {
+        fn new(input: &[&[u8]]) -> Self {
+            Self {
+                input: Cursor::new([&protocol_version_packet()[..], &input.concat()].concat()),
+                output: Vec::new(),
+            }
+        }
+    }
+
+    impl Read for MemoryStream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for MemoryStream {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Protocol version request, or response.
+    fn protocol_version_packet() -> Vec<u8> {
+        [
+            &b"ORGB"[..],                    // magic
+            &0_u32.to_le_bytes(),            // device id
+            &40_u32.to_le_bytes(),           // packet id
+            &4_u32.to_le_bytes(),            // data size
+            &DEFAULT_PROTOCOL.to_le_bytes(), // protocol version
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_negotiate_protocol() -> Result<(), OpenRGBError> {
+        let client = OpenRGBBlocking::new(MemoryStream::new(&[]))?;
+
+        assert_eq!(client.get_protocol_version(), DEFAULT_PROTOCOL);
+        assert_eq!(client.into_inner().output, protocol_version_packet());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_controller_count() -> Result<(), OpenRGBError> {
+        let mut client = OpenRGBBlocking::new(MemoryStream::new(&[
+            b"ORGB",              // magic
+            &0_u32.to_le_bytes(), // device id
+            &0_u32.to_le_bytes(), // packet id
+            &4_u32.to_le_bytes(), // data size
+            &7_u32.to_le_bytes(), // count
+        ]))?;
+
+        assert_eq!(client.get_controller_count()?, 7);
+        assert_eq!(
+            client.into_inner().output[20..],
+            [
+                &b"ORGB"[..],         // magic
+                &0_u32.to_le_bytes(), // device id
+                &0_u32.to_le_bytes(), // packet id
+                &0_u32.to_le_bytes(), // data size
+            ]
+            .concat()
+        );
+
+        Ok(())
+    }
+}
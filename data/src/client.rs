@@ -0,0 +1,1009 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+
+use genio::{Read, Write};
+
+use crate::OpenRGBError::{self, *};
+use crate::PacketId::{self, *};
+use crate::{
+    from_debug, Color, Connection, ConnectionEvent, Controller, DecodeLimits, Mode,
+    OpenRGBReadable, OpenRGBWritable, Plugin, RawBytes, RawString, Reply, RequestId, Segment,
+    DEFAULT_PROTOCOL,
+};
+
+/// OpenRGB client over any [genio] stream.
+///
+/// This client only requires `alloc`, so it can run on embedded targets with their own network
+/// stack. It has the same API as the async `openrgb` crate client, but requests block until the
+/// stream returns a reply.
+///
+/// Requests are encoded and replies decoded by a [Connection]. Server notifications received
+/// while waiting for a reply are ignored, except by [OpenRGBClient::get_all_controllers].
+///
+/// See [OpenRGBBlocking](crate::OpenRGBBlocking) for a client over [std::io] streams.
+pub struct OpenRGBClient<S: Read + Write> {
+    connection: Connection,
+    stream: S,
+
+    /// Whether a [ConnectionEvent::DeviceListUpdated] event was received, see [OpenRGBClient::get_all_controllers].
+    device_list_updated: bool,
+}
+
+impl<S: Read + Write> OpenRGBClient<S>
+where
+    <S as Write>::WriteError: Debug,
+{
+    /// Build a new client from given stream.
+    ///
+    /// This constructor expects a connected, ready to use stream, and negotiates protocol version with server.
+    pub fn new(stream: S) -> Result<Self, OpenRGBError> {
+        let mut client = Self {
+            connection: Connection::new(),
+            stream,
+            device_list_updated: false,
+        };
+        let id = client.connection.negotiate(DEFAULT_PROTOCOL)?;
+        client.flush()?;
+        client.receive_reply(id, 0, RequestProtocolVersion)?;
+        Ok(client)
+    }
+
+    /// Get protocol version negotiated with server.
+    ///
+    /// This is the lowest between this client maximum supported version ([DEFAULT_PROTOCOL]) and server version.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#protocol-versions) for more information.
+    pub fn get_protocol_version(&self) -> u32 {
+        self.connection.protocol()
+    }
+
+    /// Set limits applied when decoding data received from server.
+    ///
+    /// Defaults to [DecodeLimits::default].
+    pub fn set_decode_limits(&mut self, limits: DecodeLimits) {
+        self.connection.set_limits(limits);
+    }
+
+    /// Set client name.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_set_client_name) for more information.
+    pub fn set_name(&mut self, name: impl Into<String>) -> Result<(), OpenRGBError> {
+        self.write_packet(0, SetClientName, RawString(name.into()))
+    }
+
+    /// Get number of controllers.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_request_controller_count) for more information.
+    pub fn get_controller_count(&mut self) -> Result<u32, OpenRGBError> {
+        self.request(0, RequestControllerCount, ())
+    }
+
+    /// Get controller data.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_request_controller_data) for more information.
+    pub fn get_controller(&mut self, controller_id: u32) -> Result<Controller, OpenRGBError> {
+        self.request(
+            controller_id,
+            RequestControllerData,
+            self.connection.protocol(),
+        )
+    }
+
+    /// Get data of several controllers.
+    ///
+    /// Requests are pipelined: all of them are sent before waiting for replies, which saves a round trip
+    /// per controller compared to calling [OpenRGBClient::get_controller] in a loop.
+    pub fn get_controllers(
+        &mut self,
+        controller_ids: impl IntoIterator<Item = u32>,
+    ) -> Result<Vec<Controller>, OpenRGBError> {
+        let controllers = self.fetch_controllers(controller_ids, false)?;
+        Ok(controllers.unwrap_or_default())
+    }
+
+    /// Get data of all controllers.
+    ///
+    /// Enumeration restarts if server device list changes meanwhile (ie: a
+    /// [ConnectionEvent::DeviceListUpdated] event is received, or controller count changes), so that
+    /// the returned controllers are a consistent snapshot of server devices.
+    pub fn get_all_controllers(&mut self) -> Result<Vec<Controller>, OpenRGBError> {
+        loop {
+            self.device_list_updated = false;
+            let count = self.get_controller_count()?;
+            // server never replies for removed controllers, so do not wait for them
+            if let Some(controllers) = self.fetch_controllers(0..count, true)? {
+                if self.get_controller_count()? == count && !self.device_list_updated {
+                    return Ok(controllers);
+                }
+            }
+        }
+    }
+
+    /// Resize a controller zone, and return refreshed controller data.
+    ///
+    /// Zone type and bounds are checked first, see [Zone::check_resize](crate::Zone::check_resize).
+    /// LED indices change after a resize, so previously fetched controller data is outdated.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_rgbcontroller_resizezone) for more information.
+    pub fn resize_zone(
+        &mut self,
+        controller_id: u32,
+        zone_id: u32,
+        new_size: u32,
+    ) -> Result<Controller, OpenRGBError> {
+        let controller = self.get_controller(controller_id)?;
+        controller
+            .zones
+            .get(zone_id as usize)
+            .ok_or_else(|| InvalidArgument(format!("unknown zone ID {}", zone_id)))?
+            .check_resize(new_size)?;
+        self.write_packet(controller_id, RGBControllerResizeZone, (zone_id, new_size))?;
+        self.get_controller(controller_id)
+    }
+
+    /// Remove all segments of a controller zone.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_rgbcontroller_clearsegments) for more information.
+    pub fn clear_segments(&mut self, controller_id: u32, zone_id: u32) -> Result<(), OpenRGBError> {
+        self.check_protocol_version("Segments", 4)?;
+        self.write_packet(controller_id, RGBControllerClearSegments, zone_id)
+    }
+
+    /// Add a segment to a controller zone.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_rgbcontroller_addsegment) for more information.
+    pub fn add_segment(
+        &mut self,
+        controller_id: u32,
+        zone_id: u32,
+        segment: Segment,
+    ) -> Result<(), OpenRGBError> {
+        self.check_protocol_version("Segments", 4)?;
+        let size = data_size(
+            zone_id.size(self.connection.protocol()) + segment.size(self.connection.protocol()),
+        )?;
+        self.write_packet(
+            controller_id,
+            RGBControllerAddSegment,
+            (size, zone_id, segment),
+        )
+    }
+
+    /// Update a single LED.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_rgbcontroller_updatesingleled) for more information.
+    pub fn update_led(
+        &mut self,
+        controller_id: u32,
+        led_id: i32,
+        color: Color,
+    ) -> Result<(), OpenRGBError> {
+        self.write_packet(controller_id, RGBControllerUpdateSingleLed, (led_id, color))
+    }
+
+    /// Update LEDs.
+    ///
+    /// Colors are borrowed, and encoded into a buffer reused across calls.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_rgbcontroller_updateleds) for more information.
+    pub fn update_leds(
+        &mut self,
+        controller_id: u32,
+        colors: impl AsRef<[Color]>,
+    ) -> Result<(), OpenRGBError> {
+        let colors = colors.as_ref();
+        let size = data_size(colors.size(self.connection.protocol()))?;
+        self.write_packet(controller_id, RGBControllerUpdateLeds, (size, colors))
+    }
+
+    /// Update a zone LEDs.
+    ///
+    /// Colors are borrowed, see [OpenRGBClient::update_leds].
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_rgbcontroller_updatezoneleds) for more information.
+    pub fn update_zone_leds(
+        &mut self,
+        controller_id: u32,
+        zone_id: u32,
+        colors: impl AsRef<[Color]>,
+    ) -> Result<(), OpenRGBError> {
+        let colors = colors.as_ref();
+        let size = data_size(
+            zone_id.size(self.connection.protocol()) + colors.size(self.connection.protocol()),
+        )?;
+        self.write_packet(
+            controller_id,
+            RGBControllerUpdateZoneLeds,
+            (size, zone_id, colors),
+        )
+    }
+
+    /// Get profiles.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_request_profile_list) for more information.
+    pub fn get_profiles(&mut self) -> Result<Vec<String>, OpenRGBError> {
+        self.check_protocol_version("Profile control", 2)?;
+        self.request::<_, (u32, Vec<String>)>(0, RequestProfileList, ())
+            .map(|(_size, profiles)| profiles)
+    }
+
+    /// Load a profile.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_request_load_profile) for more information.
+    pub fn load_profile(&mut self, name: impl Into<String>) -> Result<(), OpenRGBError> {
+        self.check_protocol_version("Profile control", 2)?;
+        self.write_packet(0, RequestLoadProfile, RawString(name.into()))
+    }
+
+    /// Save a profile.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_request_save_profile) for more information.
+    pub fn save_profile(&mut self, name: impl Into<String>) -> Result<(), OpenRGBError> {
+        self.check_protocol_version("Profile control", 2)?;
+        self.write_packet(0, RequestSaveProfile, name.into())
+    }
+
+    /// Delete a profile.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_request_delete_profile) for more information.
+    pub fn delete_profile(&mut self, name: impl Into<String>) -> Result<(), OpenRGBError> {
+        self.check_protocol_version("Profile control", 2)?;
+        self.write_packet(0, RequestDeleteProfile, name.into())
+    }
+
+    /// Set custom mode.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_rgbcontroller_setcustommode) for more information.
+    pub fn set_custom_mode(&mut self, controller_id: u32) -> Result<(), OpenRGBError> {
+        self.write_packet(controller_id, RGBControllerSetCustomMode, ())
+    }
+
+    /// Update a mode.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_rgbcontroller_updatemode) for more information.
+    pub fn update_mode(
+        &mut self,
+        controller_id: u32,
+        mode_id: i32,
+        mode: Mode,
+    ) -> Result<(), OpenRGBError> {
+        let size = data_size(
+            mode_id.size(self.connection.protocol()) + mode.size(self.connection.protocol()),
+        )?;
+        self.write_packet(
+            controller_id,
+            RGBControllerUpdateMode,
+            (size, mode_id, mode),
+        )
+    }
+
+    /// Save a mode.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_rgbcontroller_savemode) for more information.
+    pub fn save_mode(&mut self, controller_id: u32, mode: Mode) -> Result<(), OpenRGBError> {
+        self.check_protocol_version("Saving modes", 3)?;
+        self.write_packet(controller_id, RGBControllerSaveMode, mode)
+    }
+
+    /// Get SDK plugins loaded by server.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_request_plugin_list) for more information.
+    pub fn get_plugins(&mut self) -> Result<Vec<Plugin>, OpenRGBError> {
+        self.check_protocol_version("Plugins", 4)?;
+        self.request::<_, (u32, Vec<Plugin>)>(0, RequestPluginList, ())
+            .map(|(_size, plugins)| plugins)
+    }
+
+    /// Send a plugin specific request, and return plugin reply.
+    ///
+    /// `plugin_id` is the plugin [index](Plugin::index). Request and reply data formats are
+    /// defined by the plugin.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_plugin_specific) for more information.
+    pub fn plugin_request(&mut self, plugin_id: u32, data: &[u8]) -> Result<Vec<u8>, OpenRGBError> {
+        self.check_protocol_version("Plugins", 4)?;
+        self.raw_request(plugin_id, PluginSpecific.into(), data)
+    }
+
+    /// Send a raw packet.
+    ///
+    /// This allows using packets not supported by this client yet.
+    pub fn write_raw_packet(
+        &mut self,
+        device_id: u32,
+        packet_id: u32,
+        data: &[u8],
+    ) -> Result<(), OpenRGBError> {
+        self.write_packet(device_id, packet_id.into(), RawBytes(data))
+    }
+
+    /// Send a raw packet, and return raw payload of reply with same device and packet IDs.
+    ///
+    /// This allows using packets not supported by this client yet. Fails with
+    /// [OpenRGBError::InvalidArgument] for [PacketId::DeviceListUpdated], as packets with this ID
+    /// are handled as notifications instead.
+    pub fn raw_request(
+        &mut self,
+        device_id: u32,
+        packet_id: u32,
+        data: &[u8],
+    ) -> Result<Vec<u8>, OpenRGBError> {
+        if PacketId::from(packet_id) == DeviceListUpdated {
+            return Err(InvalidArgument(format!(
+                "packet ID {} is reserved for device list notifications",
+                packet_id
+            )));
+        }
+        let id = self
+            .connection
+            .request(device_id, packet_id.into(), RawBytes(data))?;
+        self.flush()?;
+        Ok(self
+            .receive_reply(id, device_id, packet_id.into())?
+            .into_packet()
+            .payload)
+    }
+
+    /// Unwrap underlying stream.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    fn write_packet<I: OpenRGBWritable>(
+        &mut self,
+        device_id: u32,
+        packet_id: PacketId,
+        data: I,
+    ) -> Result<(), OpenRGBError> {
+        self.connection.send(device_id, packet_id, data)?;
+        self.flush()
+    }
+
+    fn request<I: OpenRGBWritable, O: OpenRGBReadable>(
+        &mut self,
+        device_id: u32,
+        packet_id: PacketId,
+        data: I,
+    ) -> Result<O, OpenRGBError> {
+        let id = self.connection.request(device_id, packet_id, data)?;
+        self.flush()?;
+        self.receive_reply(id, device_id, packet_id)?.decode()
+    }
+
+    /// Write packets encoded by connection to stream, keeping its buffer for next packets.
+    fn flush(&mut self) -> Result<(), OpenRGBError> {
+        let outgoing = self.connection.outgoing();
+        let len = outgoing.len();
+        self.stream.write_all(outgoing).map_err(from_debug)?;
+        self.connection.advance_outgoing(len);
+        Ok(())
+    }
+
+    /// Send controller data requests, then receive their replies in order.
+    ///
+    /// If `interruptible`, returns `None` as soon as server device list changes, and cancels
+    /// requests still waiting for their reply.
+    fn fetch_controllers(
+        &mut self,
+        controller_ids: impl IntoIterator<Item = u32>,
+        interruptible: bool,
+    ) -> Result<Option<Vec<Controller>>, OpenRGBError> {
+        let protocol = self.connection.protocol();
+        let mut requests = Vec::new();
+        for controller_id in controller_ids {
+            let id = self
+                .connection
+                .request(controller_id, RequestControllerData, protocol)?;
+            requests.push((controller_id, id));
+        }
+        self.flush()?;
+
+        let mut controllers = Vec::with_capacity(requests.len());
+        for (index, &(controller_id, id)) in requests.iter().enumerate() {
+            let reply = loop {
+                match self.wait_reply(id, controller_id, RequestControllerData) {
+                    Ok(None) if !interruptible => continue,
+                    reply => break reply,
+                }
+            };
+            match reply.and_then(|reply| reply.map(|reply| reply.decode()).transpose()) {
+                Ok(Some(controller)) => controllers.push(controller),
+                result => {
+                    for &(_, id) in &requests[index..] {
+                        self.connection.cancel(id);
+                    }
+                    return result.map(|_| None);
+                }
+            }
+        }
+        Ok(Some(controllers))
+    }
+
+    /// Read from stream until reply to given request is received.
+    ///
+    /// Packets with an unknown ID are skipped, other unexpected packets are an error, after which
+    /// the request no longer waits for its reply.
+    fn receive_reply(
+        &mut self,
+        id: RequestId,
+        device_id: u32,
+        packet_id: PacketId,
+    ) -> Result<Reply, OpenRGBError> {
+        loop {
+            if let Some(reply) = self.wait_reply(id, device_id, packet_id)? {
+                return Ok(reply);
+            }
+        }
+    }
+
+    /// Read from stream until reply to given request is received, or server device list changes.
+    ///
+    /// See [OpenRGBClient::receive_reply].
+    fn wait_reply(
+        &mut self,
+        id: RequestId,
+        device_id: u32,
+        packet_id: PacketId,
+    ) -> Result<Option<Reply>, OpenRGBError> {
+        loop {
+            while let Some(event) = self.connection.poll_event() {
+                match event {
+                    ConnectionEvent::Reply(reply) if reply.id() == id => return Ok(Some(reply)),
+                    ConnectionEvent::DeviceListUpdated => {
+                        self.device_list_updated = true;
+                        return Ok(None);
+                    }
+                    ConnectionEvent::Unexpected(packet)
+                        if !matches!(packet.header.packet_id, Unknown(_)) =>
+                    {
+                        self.connection.cancel(id);
+                        let header = packet.header;
+                        return Err(if header.packet_id == packet_id {
+                            DeviceIDMismatch {
+                                expected: device_id,
+                                got: header.device_id,
+                            }
+                        } else {
+                            PacketIDMismatch {
+                                expected: packet_id.into(),
+                                got: header.packet_id.into(),
+                            }
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            let mut buf = vec![0; self.connection.bytes_needed()];
+            self.stream
+                .read_exact(&mut buf)
+                .map_err(|_| CommunicationError("failed reading reply".to_string()))?;
+            self.connection.receive(&buf)?;
+        }
+    }
+
+    fn check_protocol_version(
+        &self,
+        operation: &str,
+        min_protocol_version: u32,
+    ) -> Result<(), OpenRGBError> {
+        let protocol = self.connection.protocol();
+        if protocol < min_protocol_version {
+            return Err(UnsupportedOperation {
+                operation: operation.to_string(),
+                current_protocol_version: protocol,
+                min_protocol_version,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Convert payload size to its wire representation.
+fn data_size(size: usize) -> Result<u32, OpenRGBError> {
+    size.try_into()
+        .map_err(|_| ProtocolError("payload too large".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use alloc::{format, vec};
+
+    use genio::{Read, Write};
+
+    use crate::protocol::{OpenRGBWritableSync, WriteVec};
+    use crate::tests::peak_allocation;
+    use crate::PacketId::RequestControllerData;
+    use crate::{
+        Color, Controller, DeviceType, OpenRGBClient, OpenRGBError, Segment, Zone, ZoneType,
+        DEFAULT_PROTOCOL,
+    };
+
+    /// In-memory stream, reading from a fixed input and recording output.
+    struct MemoryStream {
+        input: Vec<u8>,
+        position: usize,
+        output: Vec<u8>,
+    }
+
+    impl MemoryStream {
+        fn new(input: &[&[u8]]) -> Self {
+            Self {
+                input: [
+                    &protocol_version_packet(DEFAULT_PROTOCOL)[..],
+                    &input.concat(),
+                ]
+                .concat(),
+                position: 0,
+                output: Vec::new(),
+            }
+        }
+    }
+
+    impl Read for MemoryStream {
+        type ReadError = OpenRGBError;
+
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::ReadError> {
+            let len = buf.len().min(self.input.len() - self.position);
+            buf[..len].copy_from_slice(&self.input[self.position..self.position + len]);
+            self.position += len;
+            Ok(len)
+        }
+    }
+
+    impl Write for MemoryStream {
+        type WriteError = OpenRGBError;
+        type FlushError = OpenRGBError;
+
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Self::WriteError> {
+            self.output.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<(), Self::FlushError> {
+            Ok(())
+        }
+
+        fn size_hint(&mut self, _bytes: usize) {}
+    }
+
+    /// Protocol version request, or response.
+    fn protocol_version_packet(protocol: u32) -> Vec<u8> {
+        [
+            &b"ORGB"[..],            // magic
+            &0_u32.to_le_bytes(),    // device id
+            &40_u32.to_le_bytes(),   // packet id
+            &4_u32.to_le_bytes(),    // data size
+            &protocol.to_le_bytes(), // protocol version
+        ]
+        .concat()
+    }
+
+    /// Controller data reply, for a controller with a single zone of given type and LED count.
+    fn controller_packet(controller_id: u32, r#type: ZoneType, leds_count: u32) -> Vec<u8> {
+        let controller = Controller {
+            r#type: DeviceType::LEDStrip,
+            name: "Test Strip".into(),
+            vendor: "".into(),
+            description: "".into(),
+            version: "".into(),
+            serial: "".into(),
+            location: "".into(),
+            active_mode: 0,
+            modes: vec![],
+            zones: vec![Zone {
+                name: "Strip".into(),
+                r#type,
+                leds_min: 1,
+                leds_max: 40,
+                leds_count,
+                matrix: None,
+                segments: vec![],
+            }],
+            leds: vec![],
+            colors: vec![],
+        };
+        let mut packet = Vec::new();
+        WriteVec::new(&mut packet)
+            .write_packet(
+                DEFAULT_PROTOCOL,
+                controller_id,
+                RequestControllerData,
+                controller,
+            )
+            .unwrap();
+        packet
+    }
+
+    #[test]
+    fn test_negotiate_protocol() -> Result<(), OpenRGBError> {
+        let client = OpenRGBClient::new(MemoryStream::new(&[]))?;
+
+        assert_eq!(client.get_protocol_version(), DEFAULT_PROTOCOL);
+        assert_eq!(
+            client.into_inner().output,
+            protocol_version_packet(DEFAULT_PROTOCOL)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_negotiate_protocol_old_server() -> Result<(), OpenRGBError> {
+        let mut stream = MemoryStream::new(&[]);
+        stream.input = protocol_version_packet(1);
+
+        let mut client = OpenRGBClient::new(stream)?;
+
+        assert_eq!(client.get_protocol_version(), 1);
+        assert!(matches!(
+            client.get_profiles(),
+            Err(OpenRGBError::UnsupportedOperation {
+                current_protocol_version: 1,
+                min_protocol_version: 2,
+                ..
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_controller_count() -> Result<(), OpenRGBError> {
+        let mut client = OpenRGBClient::new(MemoryStream::new(&[
+            b"ORGB",              // magic
+            &0_u32.to_le_bytes(), // device id
+            &0_u32.to_le_bytes(), // packet id
+            &4_u32.to_le_bytes(), // data size
+            &7_u32.to_le_bytes(), // count
+        ]))?;
+
+        assert_eq!(client.get_controller_count()?, 7);
+        assert_eq!(
+            client.into_inner().output[20..],
+            [
+                &b"ORGB"[..],         // magic
+                &0_u32.to_le_bytes(), // device id
+                &0_u32.to_le_bytes(), // packet id
+                &0_u32.to_le_bytes(), // data size
+            ]
+            .concat()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_controller_count_after_notification() -> Result<(), OpenRGBError> {
+        let mut client = OpenRGBClient::new(MemoryStream::new(&[
+            b"ORGB",                // magic
+            &0_u32.to_le_bytes(),   // device id
+            &100_u32.to_le_bytes(), // packet id
+            &0_u32.to_le_bytes(),   // data size
+            b"ORGB",                // magic
+            &0_u32.to_le_bytes(),   // device id
+            &0_u32.to_le_bytes(),   // packet id
+            &4_u32.to_le_bytes(),   // data size
+            &7_u32.to_le_bytes(),   // count
+        ]))?;
+
+        assert_eq!(client.get_controller_count()?, 7);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_controllers() -> Result<(), OpenRGBError> {
+        let mut client = OpenRGBClient::new(MemoryStream::new(&[
+            &controller_packet(0, ZoneType::Linear, 3),
+            &controller_packet(1, ZoneType::Single, 1),
+        ]))?;
+
+        let controllers = client.get_controllers(0..2)?;
+        assert_eq!(controllers[0].zones[0].r#type, ZoneType::Linear);
+        assert_eq!(controllers[1].zones[0].r#type, ZoneType::Single);
+        assert_eq!(
+            client.into_inner().output[20..],
+            [0_u32, 1]
+                .map(|controller_id| [
+                    &b"ORGB"[..],                    // magic
+                    &controller_id.to_le_bytes(),    // device id
+                    &1_u32.to_le_bytes(),            // packet id
+                    &4_u32.to_le_bytes(),            // data size
+                    &DEFAULT_PROTOCOL.to_le_bytes(), // protocol version
+                ]
+                .concat())
+                .concat()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_all_controllers_device_removed() -> Result<(), OpenRGBError> {
+        let count = |count: u32| {
+            [
+                &b"ORGB"[..],         // magic
+                &0_u32.to_le_bytes(), // device id
+                &0_u32.to_le_bytes(), // packet id
+                &4_u32.to_le_bytes(), // data size
+                &count.to_le_bytes(), // count
+            ]
+            .concat()
+        };
+        let mut client = OpenRGBClient::new(MemoryStream::new(&[
+            &count(2),
+            &controller_packet(0, ZoneType::Linear, 3),
+            // controller 1 removed, server never replies for it
+            b"ORGB",                // magic
+            &0_u32.to_le_bytes(),   // device id
+            &100_u32.to_le_bytes(), // packet id
+            &0_u32.to_le_bytes(),   // data size
+            &count(1),
+            &controller_packet(0, ZoneType::Linear, 3),
+            &count(1),
+        ]))?;
+
+        assert_eq!(client.get_all_controllers()?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_controller_bad_packet_id() -> Result<(), OpenRGBError> {
+        let mut client = OpenRGBClient::new(MemoryStream::new(&[
+            b"ORGB",              // magic
+            &0_u32.to_le_bytes(), // device id
+            &0_u32.to_le_bytes(), // packet id
+            &4_u32.to_le_bytes(), // data size
+            &7_u32.to_le_bytes(), // count
+        ]))?;
+
+        assert!(matches!(
+            client.get_controller(0),
+            Err(OpenRGBError::PacketIDMismatch {
+                expected: 1,
+                got: 0
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_controller_after_mismatch() -> Result<(), OpenRGBError> {
+        let mut client = OpenRGBClient::new(MemoryStream::new(&[
+            b"ORGB",              // magic
+            &1_u32.to_le_bytes(), // device id
+            &1_u32.to_le_bytes(), // packet id
+            &0_u32.to_le_bytes(), // data size
+            &controller_packet(0, ZoneType::Linear, 3),
+        ]))?;
+
+        assert!(matches!(
+            client.get_controller(0),
+            Err(OpenRGBError::DeviceIDMismatch {
+                expected: 0,
+                got: 1
+            })
+        ));
+        assert_eq!(client.get_controller(0)?.zones[0].leds_count, 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_name() -> Result<(), OpenRGBError> {
+        let mut client = OpenRGBClient::new(MemoryStream::new(&[]))?;
+
+        client.set_name("test")?;
+
+        assert_eq!(
+            client.into_inner().output[20..],
+            [
+                &b"ORGB"[..],          // magic
+                &0_u32.to_le_bytes(),  // device id
+                &50_u32.to_le_bytes(), // packet id
+                &5_u32.to_le_bytes(),  // data size
+                b"test\0",             // name
+            ]
+            .concat()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_profiles() -> Result<(), OpenRGBError> {
+        let mut client = OpenRGBClient::new(MemoryStream::new(&[
+            b"ORGB",                // magic
+            &0_u32.to_le_bytes(),   // device id
+            &150_u32.to_le_bytes(), // packet id
+            &13_u32.to_le_bytes(),  // data size
+            &9_u32.to_le_bytes(),   // profiles size
+            &1_u16.to_le_bytes(),   // profiles count
+            &5_u16.to_le_bytes(),   // profile name length
+            b"test\0",              // profile name
+        ]))?;
+
+        assert_eq!(client.get_profiles()?, vec![format!("test")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_leds() -> Result<(), OpenRGBError> {
+        let mut client = OpenRGBClient::new(MemoryStream::new(&[]))?;
+
+        client.update_leds(
+            3,
+            vec![Color { r: 1, g: 2, b: 3 }, Color { r: 4, g: 5, b: 6 }],
+        )?;
+
+        assert_eq!(
+            client.into_inner().output[20..],
+            [
+                &b"ORGB"[..],            // magic
+                &3_u32.to_le_bytes(),    // device id
+                &1050_u32.to_le_bytes(), // packet id
+                &14_u32.to_le_bytes(),   // data size
+                &10_u32.to_le_bytes(),   // colors size
+                &2_u16.to_le_bytes(),    // colors count
+                &[1, 2, 3, 0],           // color 1
+                &[4, 5, 6, 0],           // color 2
+            ]
+            .concat()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_leds_no_allocation() -> Result<(), OpenRGBError> {
+        let mut stream = MemoryStream::new(&[]);
+        stream.output.reserve(0x1000);
+        let mut client = OpenRGBClient::new(stream)?;
+        let colors = vec![Color { r: 1, g: 2, b: 3 }; 300];
+
+        client.update_leds(3, colors.as_slice())?;
+        let (result, allocated) = peak_allocation(|| client.update_leds(3, colors.as_slice()));
+        result?;
+        assert_eq!(allocated, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_plugin_request() -> Result<(), OpenRGBError> {
+        let mut client = OpenRGBClient::new(MemoryStream::new(&[
+            b"ORGB",                // magic
+            &2_u32.to_le_bytes(),   // device id
+            &201_u32.to_le_bytes(), // packet id
+            &2_u32.to_le_bytes(),   // data size
+            &[4, 5],                // reply
+        ]))?;
+
+        assert_eq!(client.plugin_request(2, &[1, 2, 3])?, vec![4, 5]);
+        assert_eq!(
+            client.into_inner().output[20..],
+            [
+                &b"ORGB"[..],           // magic
+                &2_u32.to_le_bytes(),   // device id
+                &201_u32.to_le_bytes(), // packet id
+                &3_u32.to_le_bytes(),   // data size
+                &[1, 2, 3],             // request
+            ]
+            .concat()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_raw_request_device_list_updated() -> Result<(), OpenRGBError> {
+        let mut client = OpenRGBClient::new(MemoryStream::new(&[]))?;
+
+        assert!(matches!(
+            client.raw_request(0, 100, &[]),
+            Err(OpenRGBError::InvalidArgument(_))
+        ));
+
+        // no request was sent
+        assert_eq!(client.into_inner().output.len(), 20);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resize_zone() -> Result<(), OpenRGBError> {
+        let mut client = OpenRGBClient::new(MemoryStream::new(&[
+            &controller_packet(2, ZoneType::Linear, 10),
+            &controller_packet(2, ZoneType::Linear, 20),
+        ]))?;
+
+        let controller = client.resize_zone(2, 0, 20)?;
+        assert_eq!(controller.zones[0].leds_count, 20);
+
+        let output = client.into_inner().output;
+        assert_eq!(
+            output[20 + 20..20 + 20 + 24],
+            [
+                &b"ORGB"[..],            // magic
+                &2_u32.to_le_bytes(),    // device id
+                &1000_u32.to_le_bytes(), // packet id
+                &8_u32.to_le_bytes(),    // data size
+                &0_u32.to_le_bytes(),    // zone id
+                &20_u32.to_le_bytes(),   // new size
+            ]
+            .concat()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resize_zone_invalid() -> Result<(), OpenRGBError> {
+        let mut client = OpenRGBClient::new(MemoryStream::new(&[
+            &controller_packet(2, ZoneType::Linear, 10),
+            &controller_packet(2, ZoneType::Matrix, 10),
+            &controller_packet(2, ZoneType::Linear, 10),
+        ]))?;
+
+        assert!(matches!(
+            client.resize_zone(2, 0, 41),
+            Err(OpenRGBError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            client.resize_zone(2, 0, 20),
+            Err(OpenRGBError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            client.resize_zone(2, 1, 20),
+            Err(OpenRGBError::InvalidArgument(_))
+        ));
+
+        // no resize request was sent
+        assert_eq!(client.into_inner().output.len(), 20 + 3 * 20);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_segment() -> Result<(), OpenRGBError> {
+        let mut client = OpenRGBClient::new(MemoryStream::new(&[]))?;
+
+        client.add_segment(
+            3,
+            1,
+            Segment {
+                name: "Fan 2".to_string(),
+                r#type: ZoneType::Linear,
+                start_idx: 12,
+                leds_count: 12,
+            },
+        )?;
+
+        assert_eq!(
+            client.into_inner().output[20..],
+            [
+                &b"ORGB"[..],            // magic
+                &3_u32.to_le_bytes(),    // device id
+                &1002_u32.to_le_bytes(), // packet id
+                &28_u32.to_le_bytes(),   // data size
+                &24_u32.to_le_bytes(),   // segment data size
+                &1_u32.to_le_bytes(),    // zone id
+                &6_u16.to_le_bytes(),    // name len
+                b"Fan 2\0",              // name
+                &1_u32.to_le_bytes(),    // type
+                &12_u32.to_le_bytes(),   // start_idx
+                &12_u32.to_le_bytes(),   // leds_count
+            ]
+            .concat()
+        );
+
+        Ok(())
+    }
+}
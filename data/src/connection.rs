@@ -0,0 +1,557 @@
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::vec::Vec;
+
+use crate::protocol::{OpenRGBReadableSync, OpenRGBWritableSync, WriteVec};
+use crate::OpenRGBError::{self, ProtocolError};
+use crate::PacketId::{self, *};
+use crate::{DecodeLimits, Header, Limited, OpenRGBReadable, OpenRGBWritable, DEFAULT_PROTOCOL};
+
+/// Size of packet header on the wire.
+const HEADER_SIZE: usize = 4 /* magic */ + 4 /* device id */ + 4 /* packet id */ + 4 /* len */;
+
+/// Identifier of a request sent through a [Connection].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct RequestId(u64);
+
+/// Raw packet received from server.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Packet {
+    /// Packet header.
+    pub header: Header,
+
+    /// Packet data, not decoded.
+    pub payload: Vec<u8>,
+}
+
+/// Reply to a request sent through a [Connection].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Reply {
+    id: RequestId,
+    packet: Packet,
+    protocol: u32,
+    limits: DecodeLimits,
+}
+
+impl Reply {
+    /// Identifier of the request this is a reply to.
+    pub fn id(&self) -> RequestId {
+        self.id
+    }
+
+    /// Decode reply data, with the protocol version the request was sent with.
+    pub fn decode<O: OpenRGBReadable>(&self) -> Result<O, OpenRGBError> {
+        let payload = &self.packet.payload;
+        Limited::new(payload.as_slice(), self.limits).read_bounded(payload.len(), self.protocol)
+    }
+
+    /// Unwrap raw reply packet.
+    pub fn into_packet(self) -> Packet {
+        self.packet
+    }
+}
+
+/// Event yielded by a [Connection] from data received from server.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ConnectionEvent {
+    /// Reply to a request.
+    Reply(Reply),
+
+    /// Server device list changed, controller IDs and data may be outdated.
+    DeviceListUpdated,
+
+    /// Packet not matching any request, such as a packet unknown to this crate.
+    Unexpected(Packet),
+}
+
+/// Request waiting for its reply.
+#[derive(Debug)]
+struct Pending {
+    id: RequestId,
+    device_id: u32,
+    packet_id: PacketId,
+    protocol: u32,
+
+    /// Maximum protocol version, if this is a protocol version negotiation.
+    negotiate: Option<u32>,
+
+    /// Whether reply is to be discarded, see [Connection::cancel].
+    cancelled: bool,
+}
+
+/// OpenRGB protocol state machine, independent of any I/O.
+///
+/// Feed it data received from server with [Connection::receive], and get replies and
+/// notifications with [Connection::poll_event]. Requests are encoded to an outgoing buffer, to be
+/// written to server with [Connection::outgoing] and [Connection::advance_outgoing].
+///
+/// This allows embedding the protocol in any event loop. [OpenRGBClient](crate::OpenRGBClient)
+/// drives a connection over a blocking stream.
+///
+/// # Example
+///
+/// ```
+/// # use openrgb_data::{Connection, ConnectionEvent, OpenRGBError};
+/// # fn main() -> Result<(), OpenRGBError> {
+/// let mut connection = Connection::new();
+/// let request = connection.negotiate(4)?;
+/// let _request_bytes = connection.take_outgoing(); // write these to server
+///
+/// connection.receive(b"ORGB\0\0\0\0\x28\0\0\0\x04\0\0\0\x03\0\0\0")?; // read from server
+/// match connection.poll_event() {
+///     Some(ConnectionEvent::Reply(reply)) if reply.id() == request => {
+///         assert_eq!(reply.decode::<u32>()?, 3);
+///     }
+///     _ => unreachable!(),
+/// }
+/// assert_eq!(connection.protocol(), 3);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Connection {
+    protocol: u32,
+    limits: DecodeLimits,
+    next_id: u64,
+    pending: VecDeque<Pending>,
+    incoming: Vec<u8>,
+    outgoing: Vec<u8>,
+    events: VecDeque<ConnectionEvent>,
+}
+
+impl Default for Connection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Connection {
+    /// Create a new connection, using [DEFAULT_PROTOCOL] until negotiated otherwise.
+    pub fn new() -> Self {
+        Self {
+            protocol: DEFAULT_PROTOCOL,
+            limits: DecodeLimits::default(),
+            next_id: 0,
+            pending: VecDeque::new(),
+            incoming: Vec::new(),
+            outgoing: Vec::new(),
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Get protocol version used to encode and decode packets.
+    pub fn protocol(&self) -> u32 {
+        self.protocol
+    }
+
+    /// Use given protocol version, without negotiating it with server.
+    ///
+    /// Fails with [OpenRGBError::InvalidArgument] if version is above [DEFAULT_PROTOCOL].
+    pub fn set_protocol(&mut self, protocol: u32) -> Result<(), OpenRGBError> {
+        self.protocol = check_protocol(protocol)?;
+        Ok(())
+    }
+
+    /// Set limits applied when decoding data received from server.
+    pub fn set_limits(&mut self, limits: DecodeLimits) {
+        self.limits = limits;
+    }
+
+    /// Request protocol version negotiation, up to given version.
+    ///
+    /// Once the reply is received, [Connection::protocol] returns the lowest between given
+    /// version and server version. Requests sent before are encoded with the previous version.
+    /// Fails with [OpenRGBError::InvalidArgument] if version is above [DEFAULT_PROTOCOL].
+    pub fn negotiate(&mut self, max_protocol: u32) -> Result<RequestId, OpenRGBError> {
+        let max_protocol = check_protocol(max_protocol)?;
+        let id = self.request(0, RequestProtocolVersion, max_protocol)?;
+        if let Some(pending) = self.pending.back_mut() {
+            pending.negotiate = Some(max_protocol);
+        }
+        Ok(id)
+    }
+
+    /// Encode a packet, without expecting a reply.
+    pub fn send<I: OpenRGBWritable>(
+        &mut self,
+        device_id: u32,
+        packet_id: PacketId,
+        data: I,
+    ) -> Result<(), OpenRGBError> {
+        WriteVec::new(&mut self.outgoing).write_packet(self.protocol, device_id, packet_id, data)
+    }
+
+    /// Encode a packet, expecting a reply with same device and packet IDs.
+    pub fn request<I: OpenRGBWritable>(
+        &mut self,
+        device_id: u32,
+        packet_id: PacketId,
+        data: I,
+    ) -> Result<RequestId, OpenRGBError> {
+        self.send(device_id, packet_id, data)?;
+        let id = RequestId(self.next_id);
+        self.next_id += 1;
+        self.pending.push_back(Pending {
+            id,
+            device_id,
+            packet_id,
+            protocol: self.protocol,
+            negotiate: None,
+            cancelled: false,
+        });
+        Ok(id)
+    }
+
+    /// Stop waiting for the reply to given request.
+    ///
+    /// Server may never reply, eg: to a request for a controller removed meanwhile, so a later
+    /// request with same device and packet IDs receives the next matching reply. One other
+    /// matching reply is then discarded, as it may be a late reply to a cancelled request. Only
+    /// the oldest cancelled request is kept for given device and packet IDs, so requests that
+    /// never get a reply do not accumulate. Returns whether request was still waiting for its
+    /// reply.
+    pub fn cancel(&mut self, id: RequestId) -> bool {
+        let position = match self
+            .pending
+            .iter()
+            .position(|pending| pending.id == id && !pending.cancelled)
+        {
+            Some(position) => position,
+            None => return false,
+        };
+        let cancelled = &self.pending[position];
+        let superseded = self.pending.iter().take(position).any(|pending| {
+            pending.cancelled
+                && pending.device_id == cancelled.device_id
+                && pending.packet_id == cancelled.packet_id
+        });
+        if superseded {
+            self.pending.remove(position);
+        } else {
+            self.pending[position].cancelled = true;
+        }
+        true
+    }
+
+    /// Number of requests waiting for their reply.
+    pub fn pending_requests(&self) -> usize {
+        self.pending
+            .iter()
+            .filter(|pending| !pending.cancelled)
+            .count()
+    }
+
+    /// Get encoded data waiting to be written to server.
+    pub fn outgoing(&self) -> &[u8] {
+        &self.outgoing
+    }
+
+    /// Mark the first `len` bytes of [Connection::outgoing] as written.
+    pub fn advance_outgoing(&mut self, len: usize) {
+        self.outgoing.drain(..len.min(self.outgoing.len()));
+    }
+
+    /// Take all encoded data waiting to be written to server.
+    pub fn take_outgoing(&mut self) -> Vec<u8> {
+        core::mem::take(&mut self.outgoing)
+    }
+
+    /// Number of bytes still needed to complete the next packet.
+    ///
+    /// Reading exactly this number of bytes never reads past the end of a packet, which suits
+    /// blocking streams.
+    pub fn bytes_needed(&self) -> usize {
+        match self.incoming_header() {
+            Some(Ok(header)) if header.len as usize <= self.limits.max_packet_size => {
+                (HEADER_SIZE + header.len as usize).saturating_sub(self.incoming.len())
+            }
+            // invalid header, fails on next receive
+            Some(_) => 0,
+            None => HEADER_SIZE - self.incoming.len(),
+        }
+    }
+
+    /// Feed data received from server.
+    ///
+    /// Complete packets are decoded into events, available with [Connection::poll_event].
+    /// Invalid data fails with [OpenRGBError::ProtocolError] or [OpenRGBError::BadMagic], after
+    /// which the connection cannot recover and should be closed.
+    pub fn receive(&mut self, data: &[u8]) -> Result<(), OpenRGBError> {
+        self.incoming.extend_from_slice(data);
+        while let Some(header) = self.incoming_header() {
+            let header = header?;
+            let len =
+                DecodeLimits::check(header.len as usize, self.limits.max_packet_size, "packet")?;
+            if self.incoming.len() < HEADER_SIZE + len {
+                break;
+            }
+            let payload = self.incoming[HEADER_SIZE..HEADER_SIZE + len].to_vec();
+            self.incoming.drain(..HEADER_SIZE + len);
+            self.handle(Packet { header, payload })?;
+        }
+        Ok(())
+    }
+
+    /// Get next event decoded from data received from server.
+    pub fn poll_event(&mut self) -> Option<ConnectionEvent> {
+        self.events.pop_front()
+    }
+
+    /// Decode header of next incoming packet, if fully received.
+    fn incoming_header(&self) -> Option<Result<Header, OpenRGBError>> {
+        let mut header = self.incoming.get(..HEADER_SIZE)?;
+        Some(header.read_value(self.protocol))
+    }
+
+    fn handle(&mut self, packet: Packet) -> Result<(), OpenRGBError> {
+        let header = &packet.header;
+        if header.packet_id == DeviceListUpdated {
+            self.events.push_back(ConnectionEvent::DeviceListUpdated);
+            return Ok(());
+        }
+
+        let matches = |pending: &Pending| {
+            pending.device_id == header.device_id && pending.packet_id == header.packet_id
+        };
+        let position = self
+            .pending
+            .iter()
+            .position(|pending| !pending.cancelled && matches(pending))
+            .or_else(|| self.pending.iter().position(matches));
+        let pending = match position.and_then(|position| self.pending.remove(position)) {
+            Some(pending) => pending,
+            None => {
+                self.events.push_back(ConnectionEvent::Unexpected(packet));
+                return Ok(());
+            }
+        };
+        if pending.cancelled {
+            return Ok(());
+        }
+
+        let reply = Reply {
+            id: pending.id,
+            packet,
+            protocol: pending.protocol,
+            limits: self.limits,
+        };
+        if let Some(max_protocol) = pending.negotiate {
+            let server_protocol = reply.decode::<u32>().map_err(|error| {
+                ProtocolError(format!("invalid protocol version reply: {}", error))
+            })?;
+            self.protocol = max_protocol.min(server_protocol);
+        }
+        self.events.push_back(ConnectionEvent::Reply(reply));
+        Ok(())
+    }
+}
+
+/// Check given protocol version is supported by this crate.
+fn check_protocol(protocol: u32) -> Result<u32, OpenRGBError> {
+    if protocol > DEFAULT_PROTOCOL {
+        return Err(OpenRGBError::InvalidArgument(format!(
+            "protocol version {} is not supported, maximum is {}",
+            protocol, DEFAULT_PROTOCOL
+        )));
+    }
+    Ok(protocol)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::PacketId::*;
+    use crate::{Connection, ConnectionEvent, OpenRGBError, DEFAULT_PROTOCOL};
+
+    fn packet(device_id: u32, packet_id: u32, data: &[u8]) -> Vec<u8> {
+        [
+            &b"ORGB"[..],                       // magic
+            &device_id.to_le_bytes(),           // device id
+            &packet_id.to_le_bytes(),           // packet id
+            &(data.len() as u32).to_le_bytes(), // data size
+            data,                               // data
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_negotiate() -> Result<(), OpenRGBError> {
+        let mut connection = Connection::new();
+
+        let id = connection.negotiate(DEFAULT_PROTOCOL)?;
+        assert_eq!(
+            connection.take_outgoing(),
+            packet(0, 40, &DEFAULT_PROTOCOL.to_le_bytes())
+        );
+        assert_eq!(connection.pending_requests(), 1);
+
+        connection.receive(&packet(0, 40, &2_u32.to_le_bytes()))?;
+        match connection.poll_event() {
+            Some(ConnectionEvent::Reply(reply)) => assert_eq!(reply.id(), id),
+            event => panic!("unexpected event {:?}", event),
+        }
+        assert_eq!(connection.protocol(), 2);
+        assert_eq!(connection.pending_requests(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsupported_protocol() -> Result<(), OpenRGBError> {
+        let mut connection = Connection::new();
+
+        assert!(matches!(
+            connection.set_protocol(DEFAULT_PROTOCOL + 1),
+            Err(OpenRGBError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            connection.negotiate(DEFAULT_PROTOCOL + 1),
+            Err(OpenRGBError::InvalidArgument(_))
+        ));
+        assert_eq!(connection.take_outgoing(), Vec::<u8>::new());
+
+        connection.set_protocol(2)?;
+        assert_eq!(connection.protocol(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_receive_partial() -> Result<(), OpenRGBError> {
+        let mut connection = Connection::new();
+        let id = connection.request(0, RequestControllerCount, ())?;
+        let reply = packet(0, 0, &3_u32.to_le_bytes());
+
+        assert_eq!(connection.bytes_needed(), 16);
+        connection.receive(&reply[..10])?;
+        assert_eq!(connection.bytes_needed(), 6);
+        connection.receive(&reply[10..18])?;
+        assert_eq!(connection.bytes_needed(), 2);
+        assert_eq!(connection.poll_event(), None);
+
+        connection.receive(&reply[18..])?;
+        assert_eq!(connection.bytes_needed(), 16);
+        match connection.poll_event() {
+            Some(ConnectionEvent::Reply(reply)) => {
+                assert_eq!(reply.id(), id);
+                assert_eq!(reply.decode::<u32>()?, 3);
+            }
+            event => panic!("unexpected event {:?}", event),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_receive_notifications() -> Result<(), OpenRGBError> {
+        let mut connection = Connection::new();
+        let id = connection.request(0, RequestControllerCount, ())?;
+
+        connection.receive(
+            &[
+                packet(0, 100, &[]),
+                packet(0, 9999, &[1, 2]),
+                packet(0, 0, &3_u32.to_le_bytes()),
+            ]
+            .concat(),
+        )?;
+
+        assert_eq!(
+            connection.poll_event(),
+            Some(ConnectionEvent::DeviceListUpdated)
+        );
+        match connection.poll_event() {
+            Some(ConnectionEvent::Unexpected(packet)) => {
+                assert_eq!(packet.header.packet_id, Unknown(9999));
+                assert_eq!(packet.payload, [1, 2]);
+            }
+            event => panic!("unexpected event {:?}", event),
+        }
+        match connection.poll_event() {
+            Some(ConnectionEvent::Reply(reply)) => assert_eq!(reply.id(), id),
+            event => panic!("unexpected event {:?}", event),
+        }
+        assert_eq!(connection.poll_event(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cancel() -> Result<(), OpenRGBError> {
+        let mut connection = Connection::new();
+        let first = connection.request(1, RequestControllerData, DEFAULT_PROTOCOL)?;
+        let second = connection.request(1, RequestControllerData, DEFAULT_PROTOCOL)?;
+
+        assert!(connection.cancel(first));
+        assert!(!connection.cancel(first));
+        assert_eq!(connection.pending_requests(), 1);
+
+        connection.receive(&packet(1, 1, &[]))?;
+        match connection.poll_event() {
+            Some(ConnectionEvent::Reply(reply)) => assert_eq!(reply.id(), second),
+            event => panic!("unexpected event {:?}", event),
+        }
+
+        // late reply to cancelled request
+        connection.receive(&packet(1, 1, &[]))?;
+        assert_eq!(connection.poll_event(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cancel_without_reply() -> Result<(), OpenRGBError> {
+        let mut connection = Connection::new();
+
+        for _ in 0..100 {
+            for device_id in 0..3 {
+                let id = connection.request(device_id, RequestControllerData, DEFAULT_PROTOCOL)?;
+                assert!(connection.cancel(id));
+            }
+        }
+        assert_eq!(connection.pending_requests(), 0);
+        assert_eq!(connection.pending.len(), 3);
+
+        let id = connection.request(1, RequestControllerData, DEFAULT_PROTOCOL)?;
+        connection.receive(&packet(1, 1, &[]))?;
+        match connection.poll_event() {
+            Some(ConnectionEvent::Reply(reply)) => assert_eq!(reply.id(), id),
+            event => panic!("unexpected event {:?}", event),
+        }
+        assert_eq!(connection.pending.len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_replies_in_request_order() -> Result<(), OpenRGBError> {
+        let mut connection = Connection::new();
+        let first = connection.request(1, RequestControllerData, DEFAULT_PROTOCOL)?;
+        let second = connection.request(2, RequestControllerData, DEFAULT_PROTOCOL)?;
+        let third = connection.request(1, RequestControllerData, DEFAULT_PROTOCOL)?;
+
+        connection.receive(&packet(2, 1, &[]))?;
+        connection.receive(&packet(1, 1, &[]))?;
+        connection.receive(&packet(1, 1, &[]))?;
+
+        let ids = core::iter::from_fn(|| match connection.poll_event() {
+            Some(ConnectionEvent::Reply(reply)) => Some(reply.id()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+        assert_eq!(ids, [second, first, third]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_receive_bad_magic() {
+        let mut connection = Connection::new();
+
+        assert!(matches!(
+            connection.receive(b"ABCDEFGHIJKLMNOPQRSTUVWXYZ"),
+            Err(OpenRGBError::BadMagic(_))
+        ));
+    }
+}
@@ -1,3 +1,4 @@
+use alloc::format;
 use alloc::string::String;
 use alloc::vec::Vec;
 
@@ -8,7 +9,7 @@ use crate::{OpenRGBError, OpenRGBWritable};
 /// RGB controller.
 ///
 /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_request_controller_data) for more information.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub struct Controller {
     /// Controller type.
     pub r#type: DeviceType,
@@ -16,7 +17,7 @@ pub struct Controller {
     /// Controller name.
     pub name: String,
 
-    /// Controller vendor.
+    /// Controller vendor (empty before protocol version 1).
     pub vendor: String,
 
     /// Controller description.
@@ -49,37 +50,48 @@ pub struct Controller {
 
 impl OpenRGBReadable for Controller {
     fn read(stream: &mut impl OpenRGBReadableSync, protocol: u32) -> Result<Self, OpenRGBError> {
-        let _data_size = stream.read_value::<u32>(protocol)?;
-        let r#type = stream.read_value(protocol)?;
-        let name = stream.read_value(protocol)?;
-        let vendor = stream.read_value(protocol)?;
-        let description = stream.read_value(protocol)?;
-        let version = stream.read_value(protocol)?;
-        let serial = stream.read_value(protocol)?;
-        let location = stream.read_value(protocol)?;
-        let _num_modes = stream.read_value::<u16>(protocol)?;
-        let active_mode = stream.read_value(protocol)?;
-        let mut modes = Vec::with_capacity(_num_modes as usize);
-        for _ in 0.._num_modes {
-            modes.push(stream.read_value(protocol)?);
-        }
-        let zones = stream.read_value(protocol)?;
-        let leds = stream.read_value(protocol)?;
-        let colors = stream.read_value(protocol)?;
-
-        Ok(Controller {
-            r#type,
-            name,
-            vendor,
-            description,
-            version,
-            serial,
-            location,
-            active_mode,
-            modes,
-            zones,
-            leds,
-            colors,
+        let data_size = stream.read_value::<u32>(protocol)?;
+        let len = (data_size as usize)
+            .checked_sub(data_size.size(protocol))
+            .ok_or_else(|| {
+                OpenRGBError::ProtocolError(format!("invalid controller data size {}", data_size))
+            })?;
+        stream.read_bounded_with(len, |stream| {
+            let r#type = stream.read_value(protocol)?;
+            let name = stream.read_value(protocol)?;
+            let vendor = if protocol >= 1 {
+                stream.read_value(protocol)?
+            } else {
+                String::new()
+            };
+            let description = stream.read_value(protocol)?;
+            let version = stream.read_value(protocol)?;
+            let serial = stream.read_value(protocol)?;
+            let location = stream.read_value(protocol)?;
+            let num_modes = stream.read_len(protocol, stream.limits().max_items, "modes")?;
+            let active_mode = stream.read_value(protocol)?;
+            let mut modes = Vec::with_capacity(num_modes);
+            for _ in 0..num_modes {
+                modes.push(stream.read_value(protocol)?);
+            }
+            let zones = stream.read_value(protocol)?;
+            let leds = stream.read_list(protocol, stream.limits().max_leds, "LEDs")?;
+            let colors = stream.read_list(protocol, stream.limits().max_leds, "colors")?;
+
+            Ok(Controller {
+                r#type,
+                name,
+                vendor,
+                description,
+                version,
+                serial,
+                location,
+                active_mode,
+                modes,
+                zones,
+                leds,
+                colors,
+            })
         })
     }
 }
@@ -90,7 +102,9 @@ impl OpenRGBWritable for Controller {
         size += 0u32.size(protocol);
         size += self.r#type.size(protocol);
         size += self.name.size(protocol);
-        size += self.vendor.size(protocol);
+        if protocol >= 1 {
+            size += self.vendor.size(protocol);
+        }
         size += self.description.size(protocol);
         size += self.version.size(protocol);
         size += self.serial.size(protocol);
@@ -115,7 +129,9 @@ impl OpenRGBWritable for Controller {
         stream.write_value(self.size(protocol) as u32, protocol)?;
         stream.write_value(self.r#type, protocol)?;
         stream.write_value(self.name, protocol)?;
-        stream.write_value(self.vendor, protocol)?;
+        if protocol >= 1 {
+            stream.write_value(self.vendor, protocol)?;
+        }
         stream.write_value(self.description, protocol)?;
         stream.write_value(self.version, protocol)?;
         stream.write_value(self.serial, protocol)?;
@@ -141,11 +157,179 @@ mod tests {
     use alloc::{format, vec};
     use num_traits::ToPrimitive;
 
-    use crate::protocol::OpenRGBReadableSync;
-    use crate::{Color, ColorMode, Controller, DeviceType, Mode, ModeFlag::*, Zone, ZoneType};
+    use crate::protocol::{OpenRGBReadableSync, OpenRGBWritableSync, WriteVec};
+    use crate::tests::peak_allocation;
+    use crate::{
+        Color, ColorMode, Controller, DecodeLimits, DeviceType, Direction, Limited, Matrix, Mode,
+        ModeFlag::*, OpenRGBError, OpenRGBWritable, Segment, Zone, ZoneType, LED,
+    };
 
     static DEFAULT_PROTOCOL: u32 = 3;
 
+    /// Controller using every protocol feature, as decoded with given protocol version.
+    fn fixture(protocol: u32) -> Controller {
+        Controller {
+            r#type: DeviceType::Motherboard,
+            name: "Test Board".to_string(),
+            vendor: if protocol >= 1 {
+                "Test Vendor".to_string()
+            } else {
+                "".to_string()
+            },
+            description: "Test Description".to_string(),
+            version: "1.0".to_string(),
+            serial: "1234".to_string(),
+            location: "I2C: /dev/i2c-1".to_string(),
+            active_mode: 1,
+            modes: vec![
+                Mode {
+                    name: "Direct".to_string(),
+                    value: 0,
+                    flags: HasPerLEDColor.into(),
+                    speed_min: None,
+                    speed_max: None,
+                    brightness_min: None,
+                    brightness_max: None,
+                    colors_min: None,
+                    colors_max: None,
+                    speed: None,
+                    brightness: None,
+                    direction: None,
+                    color_mode: Some(ColorMode::PerLED),
+                    colors: vec![],
+                },
+                Mode {
+                    name: "Breathing".to_string(),
+                    value: 2,
+                    flags: HasSpeed | HasBrightness | HasDirectionLR | HasModeSpecificColor,
+                    speed_min: Some(1),
+                    speed_max: Some(5),
+                    brightness_min: (protocol >= 3).then_some(0),
+                    brightness_max: (protocol >= 3).then_some(100),
+                    colors_min: Some(1),
+                    colors_max: Some(2),
+                    speed: Some(3),
+                    brightness: (protocol >= 3).then_some(80),
+                    direction: Some(Direction::Right),
+                    color_mode: Some(ColorMode::ModeSpecific),
+                    colors: vec![Color { r: 255, g: 0, b: 0 }],
+                },
+            ],
+            zones: vec![
+                Zone {
+                    name: "Fans".to_string(),
+                    r#type: ZoneType::Linear,
+                    leds_min: 0,
+                    leds_max: 40,
+                    leds_count: 2,
+                    matrix: None,
+                    segments: if protocol >= 4 {
+                        vec![Segment {
+                            name: "Fan 1".to_string(),
+                            r#type: ZoneType::Linear,
+                            start_idx: 0,
+                            leds_count: 2,
+                        }]
+                    } else {
+                        vec![]
+                    },
+                },
+                Zone {
+                    name: "Keys".to_string(),
+                    r#type: ZoneType::Matrix,
+                    leds_min: 2,
+                    leds_max: 2,
+                    leds_count: 2,
+                    matrix: Matrix::from_row_major(vec![0, Matrix::NO_LED, 1], 1, 3),
+                    segments: vec![],
+                },
+            ],
+            leds: vec![
+                LED {
+                    name: "Fan LED".to_string(),
+                    value: 0,
+                },
+                LED {
+                    name: "Key LED".to_string(),
+                    value: 1,
+                },
+            ],
+            colors: vec![Color { r: 1, g: 2, b: 3 }, Color { r: 4, g: 5, b: 6 }],
+        }
+    }
+
+    #[test]
+    fn test_protocol_versions() -> Result<(), OpenRGBError> {
+        for protocol in 0..=crate::DEFAULT_PROTOCOL {
+            let controller = fixture(crate::DEFAULT_PROTOCOL);
+            let size = controller.size(protocol);
+
+            let mut buf = Vec::new();
+            WriteVec::new(&mut buf).write_value(controller, protocol)?;
+            assert_eq!(buf.len(), size, "encoded size for protocol {}", protocol);
+
+            let mut input = buf.as_slice();
+            assert_eq!(
+                input.read_value::<Controller>(protocol)?,
+                fixture(protocol),
+                "decoded controller for protocol {}",
+                protocol
+            );
+            assert!(input.is_empty(), "trailing bytes for protocol {}", protocol);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_corrupted() -> Result<(), OpenRGBError> {
+        // xorshift, to get reproducible pseudo random mutations
+        let mut seed = 0x2545_f491_u32;
+        let mut random = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed as usize
+        };
+
+        for protocol in 0..=crate::DEFAULT_PROTOCOL {
+            let mut buf = Vec::new();
+            WriteVec::new(&mut buf).write_value(fixture(protocol), protocol)?;
+
+            let mut corpus: Vec<Vec<u8>> = (0..buf.len()).map(|len| buf[..len].to_vec()).collect();
+            for i in 0..buf.len() {
+                for byte in [0x00, 0x7f, 0x80, 0xff] {
+                    let mut input = buf.clone();
+                    input[i] = byte;
+                    corpus.push(input);
+                }
+            }
+            for _ in 0..1000 {
+                let mut input = buf.clone();
+                for _ in 0..1 + random() % 4 {
+                    let i = random() % input.len();
+                    input[i] = random() as u8;
+                }
+                corpus.push(input);
+            }
+
+            for input in corpus {
+                let (_, allocated) = peak_allocation(|| {
+                    let _ = Limited::new(input.as_slice(), DecodeLimits::default())
+                        .read_bounded::<Controller>(input.len(), protocol);
+                });
+                assert!(
+                    allocated < 0x10_0000,
+                    "allocated {} bytes decoding {:?}",
+                    allocated,
+                    input
+                );
+            }
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_read_001() {
         let mut input: Vec<u8> = vec![
@@ -181,8 +365,8 @@ mod tests {
             108, 32, 53, 0, 1, 0, 0, 0, 0, 0, 0, 0, 20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         ];
 
-        for (i, b) in input
-            .len()
+        // data size includes itself
+        for (i, b) in (input.len() + 4)
             .to_u32()
             .unwrap()
             .to_le_bytes()
@@ -344,6 +528,7 @@ mod tests {
                         leds_max: 20,
                         leds_count: 0,
                         matrix: None,
+                        segments: vec![],
                     },
                     Zone {
                         name: format!("Riing Channel 2"),
@@ -352,6 +537,7 @@ mod tests {
                         leds_max: 20,
                         leds_count: 0,
                         matrix: None,
+                        segments: vec![],
                     },
                     Zone {
                         name: format!("Riing Channel 3"),
@@ -360,6 +546,7 @@ mod tests {
                         leds_max: 20,
                         leds_count: 0,
                         matrix: None,
+                        segments: vec![],
                     },
                     Zone {
                         name: format!("Riing Channel 4"),
@@ -368,6 +555,7 @@ mod tests {
                         leds_max: 20,
                         leds_count: 0,
                         matrix: None,
+                        segments: vec![],
                     },
                     Zone {
                         name: format!("Riing Channel 5"),
@@ -376,6 +564,7 @@ mod tests {
                         leds_max: 20,
                         leds_count: 0,
                         matrix: None,
+                        segments: vec![],
                     },
                 ],
                 leds: vec![],
@@ -0,0 +1,434 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+use core::str::FromStr;
+
+use num_traits::FromPrimitive;
+
+use crate::{Controller, DeviceType, OpenRGBError};
+
+/// Separator between fields of a [DeviceKey] string form.
+const SEPARATOR: char = '|';
+
+/// Escape character for separators in fields of a [DeviceKey] string form.
+const ESCAPE: char = '\\';
+
+/// Identity of a device, stable across server restarts and devices being plugged in or out.
+///
+/// Controller IDs are indices in server device list, which change when devices are added or the
+/// server rescans. A key built from a controller data identifies the same device later, see
+/// [DeviceKey::score] for matching rules and [DeviceResolver] to find its current controller ID.
+///
+/// Keys can be saved using their [Display](fmt::Display) string form, parsed back with
+/// [str::parse], or with serde when `serde` feature is enabled.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceKey {
+    /// Device type.
+    pub r#type: DeviceType,
+
+    /// Device vendor (empty before protocol version 1).
+    pub vendor: String,
+
+    /// Device name.
+    pub name: String,
+
+    /// Device serial (often empty).
+    pub serial: String,
+
+    /// Device location, such as a HID path or I2C address (may change when device is plugged again).
+    pub location: String,
+}
+
+impl DeviceKey {
+    /// Score of given key matching this key, higher is better, or `None` if it does not match.
+    ///
+    /// Keys match if their type and name are equal, and their vendors and serials are equal or
+    /// empty. Equal serials score higher than equal locations, which score higher than
+    /// equal vendors, since a location may be reused by another device of the same model.
+    pub fn score(&self, other: &DeviceKey) -> Option<u32> {
+        if self.r#type != other.r#type || self.name != other.name {
+            return None;
+        }
+        let mut score = 1;
+        for (this, other, weight) in [
+            (&self.vendor, &other.vendor, 1),
+            (&self.serial, &other.serial, 4),
+        ] {
+            match (this.is_empty() || other.is_empty(), this == other) {
+                (true, _) => {}
+                (false, true) => score += weight,
+                (false, false) => return None,
+            }
+        }
+        if !self.location.is_empty() && self.location == other.location {
+            score += 2;
+        }
+        Some(score)
+    }
+}
+
+/// Stable string form, parsed back by [DeviceKey::from_str].
+///
+/// This is the numeric device type followed by vendor, name, serial and location, separated by
+/// `|`, with `|` and `\` escaped by a `\` in fields, eg: `5|Vendor|Keyboard||HID: /dev/hidraw1`.
+impl fmt::Display for DeviceKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.r#type as u32)?;
+        for field in [&self.vendor, &self.name, &self.serial, &self.location] {
+            f.write_char(SEPARATOR)?;
+            for c in field.chars() {
+                if c == SEPARATOR || c == ESCAPE {
+                    f.write_char(ESCAPE)?;
+                }
+                f.write_char(c)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DeviceKey {
+    type Err = OpenRGBError;
+
+    /// Parse a key from its [Display](fmt::Display) string form.
+    ///
+    /// Fails with [OpenRGBError::InvalidArgument] if string is not a valid key.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || OpenRGBError::InvalidArgument(format!("invalid device key {:?}", s));
+        let mut fields = Vec::with_capacity(5);
+        let mut field = String::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                ESCAPE => field.push(chars.next().ok_or_else(invalid)?),
+                SEPARATOR => fields.push(core::mem::take(&mut field)),
+                c => field.push(c),
+            }
+        }
+        fields.push(field);
+        let [r#type, vendor, name, serial, location]: [String; 5] =
+            fields.try_into().map_err(|_| invalid())?;
+        Ok(Self {
+            r#type: r#type
+                .parse()
+                .ok()
+                .and_then(DeviceType::from_u32)
+                .ok_or_else(invalid)?,
+            vendor,
+            name,
+            serial,
+            location,
+        })
+    }
+}
+
+impl From<&Controller> for DeviceKey {
+    fn from(controller: &Controller) -> Self {
+        Self {
+            r#type: controller.r#type,
+            vendor: controller.vendor.clone(),
+            name: controller.name.clone(),
+            serial: controller.serial.clone(),
+            location: controller.location.clone(),
+        }
+    }
+}
+
+/// Result of resolving a [DeviceKey], see [DeviceResolver::resolve].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Resolution {
+    /// Device has given controller ID.
+    Found(u32),
+
+    /// No current controller matches device.
+    Missing,
+
+    /// Several controllers match device equally well, with given controller IDs.
+    Ambiguous(Vec<u32>),
+}
+
+/// Maps saved [DeviceKey]s to current controller IDs.
+///
+/// # Example
+///
+/// ```
+/// # use openrgb_data::{Controller, DeviceKey, DeviceResolver, Resolution};
+/// # fn example(saved: &DeviceKey, controllers: &[Controller]) {
+/// match DeviceResolver::new(controllers).resolve(saved) {
+///     Resolution::Found(controller_id) => println!("{:?} is controller {}", saved, controller_id),
+///     Resolution::Missing => println!("{:?} is not connected", saved),
+///     Resolution::Ambiguous(ids) => println!("{:?} may be any of {:?}", saved, ids),
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DeviceResolver {
+    keys: Vec<DeviceKey>,
+}
+
+impl DeviceResolver {
+    /// Create a resolver for given controllers, in controller ID order.
+    pub fn new<'a>(controllers: impl IntoIterator<Item = &'a Controller>) -> Self {
+        Self {
+            keys: controllers.into_iter().map(DeviceKey::from).collect(),
+        }
+    }
+
+    /// Find controller ID of given device, among controllers with best [DeviceKey::score].
+    pub fn resolve(&self, key: &DeviceKey) -> Resolution {
+        let mut best = 0;
+        let mut ids = Vec::new();
+        for (id, current) in self.keys.iter().enumerate() {
+            match key.score(current) {
+                Some(score) if score > best => {
+                    best = score;
+                    ids.clear();
+                    ids.push(id as u32);
+                }
+                Some(score) if score == best => ids.push(id as u32),
+                _ => {}
+            }
+        }
+        match ids.len() {
+            0 => Resolution::Missing,
+            1 => Resolution::Found(ids[0]),
+            _ => Resolution::Ambiguous(ids),
+        }
+    }
+
+    /// Resolve given devices, assigning each controller to at most one device.
+    ///
+    /// Devices are resolved by decreasing [DeviceKey::score], each among controllers not already
+    /// assigned to another device, see [DeviceResolver::resolve]. Ambiguous devices are not
+    /// assigned any controller.
+    pub fn resolve_all<'a>(
+        &self,
+        keys: impl IntoIterator<Item = &'a DeviceKey>,
+    ) -> Vec<Resolution> {
+        let keys = keys.into_iter().collect::<Vec<_>>();
+        let mut resolutions = alloc::vec![Resolution::Missing; keys.len()];
+        let mut resolved = alloc::vec![false; keys.len()];
+        let mut assigned = alloc::vec![false; self.keys.len()];
+
+        // best score of each device among free controllers, highest first
+        let best = |resolved: &[bool], assigned: &[bool]| {
+            keys.iter()
+                .enumerate()
+                .filter(|(index, _)| !resolved[*index])
+                .filter_map(|(index, key)| {
+                    self.free(assigned)
+                        .filter_map(|(_, current)| key.score(current))
+                        .max()
+                        .map(|score| (score, index))
+                })
+                .max_by_key(|(score, index)| (*score, core::cmp::Reverse(*index)))
+        };
+        while let Some((score, index)) = best(&resolved, &assigned) {
+            let ids = self
+                .free(&assigned)
+                .filter(|(_, current)| keys[index].score(current) == Some(score))
+                .map(|(id, _)| id as u32)
+                .collect::<Vec<_>>();
+            resolutions[index] = match ids.as_slice() {
+                [id] => {
+                    assigned[*id as usize] = true;
+                    Resolution::Found(*id)
+                }
+                _ => Resolution::Ambiguous(ids),
+            };
+            resolved[index] = true;
+        }
+        resolutions
+    }
+
+    /// Controllers not assigned yet, with their ID.
+    fn free<'a>(&'a self, assigned: &'a [bool]) -> impl Iterator<Item = (usize, &'a DeviceKey)> {
+        self.keys
+            .iter()
+            .enumerate()
+            .filter(move |(id, _)| !assigned[*id])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+
+    use crate::{DeviceKey, DeviceResolver, DeviceType, OpenRGBError, Resolution};
+
+    fn key(name: &str, serial: &str, location: &str) -> DeviceKey {
+        DeviceKey {
+            r#type: DeviceType::Keyboard,
+            vendor: String::from("Vendor"),
+            name: String::from(name),
+            serial: String::from(serial),
+            location: String::from(location),
+        }
+    }
+
+    fn resolver(keys: &[DeviceKey]) -> DeviceResolver {
+        DeviceResolver {
+            keys: keys.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_score() {
+        let saved = key("Keyboard", "", "HID: /dev/hidraw1");
+
+        assert_eq!(saved.score(&saved), Some(4));
+        assert_eq!(
+            saved.score(&key("Keyboard", "", "HID: /dev/hidraw2")),
+            Some(2)
+        );
+        assert_eq!(
+            saved.score(&key("Keyboard", "1234", "HID: /dev/hidraw1")),
+            Some(4)
+        );
+        assert_eq!(saved.score(&key("Mouse", "", "HID: /dev/hidraw1")), None);
+
+        let other_type = DeviceKey {
+            r#type: DeviceType::Mouse,
+            ..saved.clone()
+        };
+        assert_eq!(saved.score(&other_type), None);
+
+        let no_vendor = DeviceKey {
+            vendor: String::new(),
+            ..saved.clone()
+        };
+        assert_eq!(saved.score(&no_vendor), Some(3));
+
+        let serial = key("Keyboard", "1234", "");
+        assert_eq!(
+            serial.score(&key("Keyboard", "1234", "HID: /dev/hidraw1")),
+            Some(6)
+        );
+        assert_eq!(
+            serial.score(&key("Keyboard", "5678", "HID: /dev/hidraw1")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_resolve_reordered() {
+        let resolver = resolver(&[
+            key("Mouse", "", "HID: /dev/hidraw0"),
+            key("Keyboard", "", "HID: /dev/hidraw3"),
+        ]);
+
+        assert_eq!(
+            resolver.resolve_all(&[
+                key("Keyboard", "", "HID: /dev/hidraw1"),
+                key("Mouse", "", "HID: /dev/hidraw0"),
+                key("Headset", "", "HID: /dev/hidraw2"),
+            ]),
+            vec![
+                Resolution::Found(1),
+                Resolution::Found(0),
+                Resolution::Missing
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_identical_devices() {
+        let resolver = resolver(&[
+            key("Fan", "", "I2C: /dev/i2c-1, address 0x28"),
+            key("Fan", "", "I2C: /dev/i2c-1, address 0x29"),
+        ]);
+
+        assert_eq!(
+            resolver.resolve(&key("Fan", "", "I2C: /dev/i2c-1, address 0x29")),
+            Resolution::Found(1)
+        );
+        assert_eq!(
+            resolver.resolve(&key("Fan", "", "I2C: /dev/i2c-2, address 0x29")),
+            Resolution::Ambiguous(vec![0, 1])
+        );
+    }
+
+    #[test]
+    fn test_resolve_all_assigns_each_controller_once() {
+        let resolver = resolver(&[
+            key("Keyboard", "5678", "HID: /dev/hidraw1"),
+            key("Keyboard", "1234", "HID: /dev/hidraw2"),
+            key("Fan", "", "I2C: /dev/i2c-1, address 0x29"),
+        ]);
+
+        assert_eq!(
+            resolver.resolve_all(&[
+                key("Keyboard", "1234", "HID: /dev/hidraw1"),
+                key("Keyboard", "5678", "HID: /dev/hidraw2"),
+                key("Fan", "", "I2C: /dev/i2c-1, address 0x28"),
+                key("Fan", "", "I2C: /dev/i2c-1, address 0x29"),
+            ]),
+            vec![
+                Resolution::Found(1),
+                Resolution::Found(0),
+                Resolution::Missing,
+                Resolution::Found(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_all_identical_devices_by_location() {
+        let resolver = resolver(&[
+            key("Fan", "", "I2C: /dev/i2c-1, address 0x29"),
+            key("Fan", "", "I2C: /dev/i2c-2, address 0x28"),
+        ]);
+
+        assert_eq!(
+            resolver.resolve_all(&[
+                key("Fan", "", "I2C: /dev/i2c-1, address 0x28"),
+                key("Fan", "", "I2C: /dev/i2c-1, address 0x29"),
+            ]),
+            vec![Resolution::Found(1), Resolution::Found(0)]
+        );
+        assert_eq!(
+            resolver.resolve_all(&[
+                key("Fan", "", "I2C: /dev/i2c-3, address 0x28"),
+                key("Fan", "", "I2C: /dev/i2c-3, address 0x29"),
+            ]),
+            vec![
+                Resolution::Ambiguous(vec![0, 1]),
+                Resolution::Ambiguous(vec![0, 1])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_string_form() -> Result<(), OpenRGBError> {
+        let saved = key("Key|board", "12\\34", "HID: /dev/hidraw1");
+
+        assert_eq!(
+            saved.to_string(),
+            "5|Vendor|Key\\|board|12\\\\34|HID: /dev/hidraw1"
+        );
+        assert_eq!(saved.to_string().parse::<DeviceKey>()?, saved);
+
+        for invalid in ["", "5|Vendor|Keyboard|", "99||||", "5||||\\"] {
+            assert!(matches!(
+                invalid.parse::<DeviceKey>(),
+                Err(OpenRGBError::InvalidArgument(_))
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() -> Result<(), serde_json::Error> {
+        let saved = key("Keyboard", "1234", "HID: /dev/hidraw1");
+
+        let json = serde_json::to_string(&saved)?;
+        assert_eq!(serde_json::from_str::<DeviceKey>(&json)?, saved);
+
+        Ok(())
+    }
+}
@@ -8,7 +8,8 @@ use crate::{OpenRGBReadable, OpenRGBWritable};
 /// RGB controller device type.
 ///
 /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation) for more information.
-#[derive(Primitive, Eq, PartialEq, Debug, Copy, Clone)]
+#[derive(Primitive, Eq, PartialEq, Hash, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DeviceType {
     /// Motherboard.
     Motherboard = 0,
@@ -76,41 +77,33 @@ impl OpenRGBReadable for DeviceType {
     }
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use std::error::Error;
+#[cfg(test)]
+mod tests {
+    use crate::tests::Builder;
+    use crate::OpenRGBError;
 
-//     use tokio_test::io::Builder;
+    use crate::protocol::{OpenRGBReadableSync, OpenRGBWritableSync};
+    use crate::DeviceType;
+    use crate::DEFAULT_PROTOCOL;
 
-//     use crate::data::DeviceType;
-//     use crate::protocol::{OpenRGBReadableStream, OpenRGBWritableStream};
-//     use crate::tests::setup;
-//     use crate::DEFAULT_PROTOCOL;
+    #[test]
+    fn test_read_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new().read(&8_u32.to_le_bytes()).build();
 
-//     #[tokio::test]
-//     async fn test_read_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
+        assert_eq!(
+            stream.read_value::<DeviceType>(DEFAULT_PROTOCOL)?,
+            DeviceType::Headset
+        );
 
-//         let mut stream = Builder::new().read(&8_u32.to_le_bytes()).build();
-
-//         assert_eq!(
-//             stream.read_value::<DeviceType>(DEFAULT_PROTOCOL).await?,
-//             DeviceType::Headset
-//         );
-
-//         Ok(())
-//     }
-
-//     #[tokio::test]
-//     async fn test_write_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
+        Ok(())
+    }
 
-//         let mut stream = Builder::new().write(&8_u32.to_le_bytes()).build();
+    #[test]
+    fn test_write_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new().write(&8_u32.to_le_bytes()).build();
 
-//         stream
-//             .write_value(DeviceType::Headset, DEFAULT_PROTOCOL)
-//             .await?;
+        stream.write_value(DeviceType::Headset, DEFAULT_PROTOCOL)?;
 
-//         Ok(())
-//     }
-// }
+        Ok(())
+    }
+}
@@ -57,41 +57,34 @@ impl OpenRGBReadable for Direction {
     }
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use std::error::Error;
+#[cfg(test)]
+mod tests {
+    use crate::tests::Builder;
+    use crate::OpenRGBError;
 
-//     use crate::data::Direction;
-//     use tokio_test::io::Builder;
+    use crate::Direction;
 
-//     use crate::protocol::{OpenRGBReadableStream, OpenRGBWritableStream};
-//     use crate::tests::setup;
-//     use crate::DEFAULT_PROTOCOL;
+    use crate::protocol::{OpenRGBReadableSync, OpenRGBWritableSync};
+    use crate::DEFAULT_PROTOCOL;
 
-//     #[tokio::test]
-//     async fn test_read_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
+    #[test]
+    fn test_read_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new().read(&4_u32.to_le_bytes()).build();
 
-//         let mut stream = Builder::new().read(&4_u32.to_le_bytes()).build();
+        assert_eq!(
+            stream.read_value::<Direction>(DEFAULT_PROTOCOL)?,
+            Direction::Horizontal
+        );
 
-//         assert_eq!(
-//             stream.read_value::<Direction>(DEFAULT_PROTOCOL).await?,
-//             Direction::Horizontal
-//         );
-
-//         Ok(())
-//     }
-
-//     #[tokio::test]
-//     async fn test_write_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
+        Ok(())
+    }
 
-//         let mut stream = Builder::new().write(&4_u32.to_le_bytes()).build();
+    #[test]
+    fn test_write_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new().write(&4_u32.to_le_bytes()).build();
 
-//         stream
-//             .write_value(Direction::Horizontal, DEFAULT_PROTOCOL)
-//             .await?;
+        stream.write_value(Direction::Horizontal, DEFAULT_PROTOCOL)?;
 
-//         Ok(())
-//     }
-// }
+        Ok(())
+    }
+}
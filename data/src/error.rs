@@ -2,20 +2,31 @@ use alloc::format;
 use alloc::string::String;
 use thiserror_no_std::Error;
 
-/// Errors returned by [OpenRGB client](crate::OpenRGB).
+/// Errors returned by [OpenRGB client](crate::OpenRGBClient).
 #[derive(Error, Debug)]
 pub enum OpenRGBError {
     /// Failed opening connection to OpenRGB server.
+    #[error("Failed opening connection to OpenRGB server")]
     ConnectionError(),
 
     /// Communication failure with OpenRGB server.
+    #[error("Failed exchanging data with OpenRGB server: {0}")]
     CommunicationError(String),
 
     /// Invalid encountered while communicating with OpenRGB server.
+    #[error("Invalid data encountered while communicating with OpenRGB server: {0}")]
     ProtocolError(String),
 
+    /// Invalid argument given to a client method.
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
     /// Server does not support operation.
+    #[error("{operation:?} is only supported since protocol version {min_protocol_version:?}, but version {current_protocol_version:?} is in use. Try upgrading the OpenRGB server.")]
     UnsupportedOperation {
+        /// Operation name.
+        operation: String,
+
         /// Protocol version in use by client.
         current_protocol_version: u32,
 
@@ -23,17 +34,31 @@ pub enum OpenRGBError {
         min_protocol_version: u32,
     },
 
+    /// Received unknown packet ID.
+    #[error("Received unknown packet ID {0}")]
     BadPacketID(u32),
 
+    /// Received invalid magic value.
+    #[error("Received invalid magic value {0}")]
     BadMagic(u8),
 
+    /// Received packet for another device than expected.
+    #[error("Expected device ID {expected}, got {got}")]
     DeviceIDMismatch {
+        /// Expected device ID.
         expected: u32,
+
+        /// Received device ID.
         got: u32,
     },
 
+    /// Received another packet than expected.
+    #[error("Expected packet ID {expected}, got {got}")]
     PacketIDMismatch {
+        /// Expected packet ID.
         expected: u32,
+
+        /// Received packet ID.
         got: u32,
     },
 }
@@ -6,7 +6,7 @@ use crate::OpenRGBReadable;
 use crate::OpenRGBWritable;
 
 /// A single LED.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub struct LED {
     /// LED name.
     pub name: String,
@@ -41,35 +41,31 @@ impl OpenRGBWritable for LED {
     }
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use std::error::Error;
+#[cfg(test)]
+mod tests {
+    use crate::tests::Builder;
+    use crate::OpenRGBError;
 
-//     use tokio_test::io::Builder;
+    use crate::protocol::OpenRGBReadableSync;
+    use crate::DEFAULT_PROTOCOL;
+    use crate::LED;
 
-//     use crate::data::LED;
-//     use crate::protocol::OpenRGBReadableStream;
-//     use crate::tests::setup;
-//     use crate::DEFAULT_PROTOCOL;
+    #[test]
+    fn test_read_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new()
+            .read(&5_u16.to_le_bytes())
+            .read(b"test\0")
+            .read(&45_u32.to_le_bytes())
+            .build();
 
-//     #[tokio::test]
-//     async fn test_read_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
+        assert_eq!(
+            stream.read_value::<LED>(DEFAULT_PROTOCOL)?,
+            LED {
+                name: "test".to_string(),
+                value: 45
+            }
+        );
 
-//         let mut stream = Builder::new()
-//             .read(&5_u16.to_le_bytes())
-//             .read(b"test\0")
-//             .read(&45_u32.to_le_bytes())
-//             .build();
-
-//         assert_eq!(
-//             stream.read_value::<LED>(DEFAULT_PROTOCOL).await?,
-//             LED {
-//                 name: "test".to_string(),
-//                 value: 45
-//             }
-//         );
-
-//         Ok(())
-//     }
-// }
+        Ok(())
+    }
+}
@@ -1,46 +1,68 @@
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 //! OpenRGB data types.
 //!
 //! See [OpenRGB SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation) for more information.
+//!
+//! This crate provides a `no_std` client over any [genio] stream, [OpenRGBClient]. With the `std`
+//! feature, it also provides a blocking client over [std::io] streams, [OpenRGBBlocking].
 extern crate alloc;
 
+#[cfg(feature = "std")]
+pub use blocking::*;
+pub use client::*;
 pub use color::*;
 pub use color_mode::*;
+pub use connection::*;
 pub use controller::*;
+pub use device_key::*;
 pub use device_type::*;
 pub use direction::*;
 pub use error::*;
 pub use led::*;
+pub use limits::*;
 pub use mode::*;
 pub use mode_flag::*;
 #[doc(hidden)]
 pub use packet::*;
+pub use plugin::*;
 pub use primitive::*;
 pub use protocol::*;
+pub use segment::*;
 pub use string::*;
 pub use tuple::*;
 pub use vec::*;
 pub use zone::*;
 pub use zone_type::*;
 
+#[cfg(feature = "std")]
+mod blocking;
+mod client;
 mod color;
 mod color_mode;
+mod connection;
 mod controller;
+mod device_key;
 mod device_type;
 mod direction;
 mod error;
 mod led;
+mod limits;
 mod mode;
 mod mode_flag;
 mod packet;
+mod plugin;
 mod primitive;
 mod protocol;
+mod segment;
 mod string;
 mod tuple;
 mod vec;
 mod zone;
 mod zone_type;
 
+#[cfg(test)]
+mod tests;
+
 #[doc(hidden)]
 pub trait OpenRGBReadable: Sized + Send + Sync {
     fn read(stream: &mut impl OpenRGBReadableSync, protocol: u32) -> Result<Self, OpenRGBError>;
@@ -0,0 +1,162 @@
+use alloc::format;
+
+use crate::OpenRGBError::{self, ProtocolError};
+
+/// Limits applied when decoding data received from server.
+///
+/// Every length read from the wire is checked against these limits before allocating memory, so
+/// that a buggy or malicious server cannot exhaust client memory.
+///
+/// Memory used to decode a packet is bounded by [DecodeLimits::max_packet_size], as lists are
+/// only preallocated up to a small number of items. Other limits let applications reject
+/// unexpectedly large values early, and default well beyond real world hardware.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct DecodeLimits {
+    /// Maximum packet data size, in bytes.
+    ///
+    /// This bounds memory used to read and decode a single packet.
+    pub max_packet_size: usize,
+
+    /// Maximum string length, in bytes.
+    ///
+    /// Names, descriptions, serials and locations are short, this only guards against garbage.
+    pub max_string: usize,
+
+    /// Maximum number of items in lists not covered by other limits (modes, zones, segments...).
+    ///
+    /// These lists are short, this only guards against garbage.
+    pub max_items: usize,
+
+    /// Maximum number of LEDs (and LED colors) of a controller or zone.
+    ///
+    /// Defaults to the largest count the protocol can carry, as LED strips driven over E1.31
+    /// or DDP and large matrices can have thousands of LEDs.
+    pub max_leds: usize,
+
+    /// Maximum number of colors of a mode.
+    ///
+    /// Defaults to the largest count the protocol can carry, as some modes take one color per
+    /// LED.
+    pub max_colors: usize,
+}
+
+impl DecodeLimits {
+    /// Default limits, accepting any real world device while bounding memory used to decode a
+    /// packet.
+    pub const DEFAULT: DecodeLimits = DecodeLimits {
+        max_packet_size: 0x100_0000,
+        max_string: 0x1000,
+        max_items: 0x400,
+        max_leds: u16::MAX as usize,
+        max_colors: u16::MAX as usize,
+    };
+
+    /// Check that given length is within given limit.
+    pub(crate) fn check(len: usize, max: usize, what: &str) -> Result<usize, OpenRGBError> {
+        if len > max {
+            return Err(ProtocolError(format!(
+                "received {} of length {}, exceeding limit of {}",
+                what, len, max
+            )));
+        }
+        Ok(len)
+    }
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use alloc::{format, vec};
+
+    use crate::protocol::{OpenRGBReadableSync, OpenRGBWritableSync, WriteVec};
+    use crate::tests::{peak_allocation, Builder};
+    use crate::{
+        Color, Controller, DecodeLimits, DeviceType, Limited, OpenRGBError, DEFAULT_PROTOCOL, LED,
+    };
+
+    #[test]
+    fn test_read_string_exceeding_limit() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new().read(&0xffff_u16.to_le_bytes()).build();
+
+        let (result, allocated) = peak_allocation(|| stream.read_value::<String>(DEFAULT_PROTOCOL));
+        assert!(matches!(result, Err(OpenRGBError::ProtocolError(_))));
+        assert!(allocated < 0x1000, "allocated {} bytes", allocated);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_list_exceeding_custom_limit() -> Result<(), OpenRGBError> {
+        let mut stream = Limited::new(
+            Builder::new().read(&3_u16.to_le_bytes()).build(),
+            DecodeLimits {
+                max_items: 2,
+                ..DecodeLimits::default()
+            },
+        );
+
+        assert!(matches!(
+            stream.read_value::<Vec<u8>>(DEFAULT_PROTOCOL),
+            Err(OpenRGBError::ProtocolError(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_controller_with_many_leds() -> Result<(), OpenRGBError> {
+        let controller = Controller {
+            r#type: DeviceType::LEDStrip,
+            name: "Test Strip".into(),
+            vendor: "".into(),
+            description: "".into(),
+            version: "".into(),
+            serial: "".into(),
+            location: "E1.31: 192.168.1.20".into(),
+            active_mode: 0,
+            modes: vec![],
+            zones: vec![],
+            leds: (0..3000)
+                .map(|value| LED {
+                    name: format!("LED {}", value),
+                    value,
+                })
+                .collect(),
+            colors: vec![Color::default(); 3000],
+        };
+        let mut buf = Vec::new();
+        WriteVec::new(&mut buf).write_value(controller.clone(), DEFAULT_PROTOCOL)?;
+
+        let mut stream = Limited::new(buf.as_slice(), DecodeLimits::default());
+        assert_eq!(
+            stream.read_bounded::<Controller>(buf.len(), DEFAULT_PROTOCOL)?,
+            controller
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_packet_exceeding_limit() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new()
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&0_u32.to_le_bytes()) // packet id
+            .read(&u32::MAX.to_le_bytes()) // data size
+            .build();
+
+        assert!(matches!(
+            stream.read_packet::<u32>(DEFAULT_PROTOCOL, 0, Default::default()),
+            Err(OpenRGBError::ProtocolError(_))
+        ));
+
+        Ok(())
+    }
+}
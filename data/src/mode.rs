@@ -12,8 +12,6 @@ use crate::{
     OpenRGBReadable, OpenRGBWritable,
 };
 
-const MAX_COLORS: usize = 128;
-
 /// RGB controller mode.
 ///
 /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#mode-data) for more information.
@@ -37,13 +35,13 @@ pub struct Mode {
     /// Mode maximum speed (if mode has [ModeFlag::HasSpeed] flag).
     pub speed: Option<u32>,
 
-    /// Mode minimum brightness (if mode has [ModeFlag::HasBrightness] flag).
+    /// Mode minimum brightness (if mode has [ModeFlag::HasBrightness] flag, since protocol version 3).
     pub brightness_min: Option<u32>,
 
-    /// Mode maximum brightness (if mode has [ModeFlag::HasBrightness] flag).
+    /// Mode maximum brightness (if mode has [ModeFlag::HasBrightness] flag, since protocol version 3).
     pub brightness_max: Option<u32>,
 
-    /// Mode brightness (if mode has [ModeFlag::HasBrightness] flag).
+    /// Mode brightness (if mode has [ModeFlag::HasBrightness] flag, since protocol version 3).
     pub brightness: Option<u32>,
 
     /// Mode color mode.
@@ -89,7 +87,8 @@ impl OpenRGBReadable for Mode {
         };
         let direction = stream.read_value(protocol)?;
         let color_mode = stream.read_value(protocol)?;
-        let colors: Vec<Color> = stream.read_value(protocol)?;
+        let colors: Vec<Color> =
+            stream.read_list(protocol, stream.limits().max_colors, "mode colors")?;
 
         Ok(Mode {
             name,
@@ -135,7 +134,7 @@ impl OpenRGBReadable for Mode {
             } else {
                 None
             },
-            direction: if flags.contains(HasDirection) {
+            direction: if !(flags & HasDirection).is_empty() {
                 Some(
                     Direction::from_u32(direction)
                         .ok_or_else(|| ProtocolError(format!("bad direction")))?,
@@ -200,232 +199,220 @@ impl OpenRGBWritable for Mode {
     }
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use std::error::Error;
-
-//     use tokio_test::io::Builder;
-
-//     use crate::data::{Color, ColorMode, Direction, Mode, ModeFlag::*};
-//     use crate::protocol::{OpenRGBReadableStream, OpenRGBWritableStream};
-//     use crate::tests::setup;
-//     use crate::DEFAULT_PROTOCOL;
-
-//     #[tokio::test]
-//     async fn test_read_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
-
-//         let mut stream = Builder::new()
-//             .read(&5_u16.to_le_bytes()) // name len
-//             .read(b"test\0") // name
-//             .read(&46_i32.to_le_bytes()) // value
-//             .read(&31_u32.to_le_bytes()) // flags
-//             .read(&10_u32.to_le_bytes()) // speed_min
-//             .read(&1000_u32.to_le_bytes()) // speed_max
-//             .read(&1_u32.to_le_bytes()) // brightness_min
-//             .read(&1024_u32.to_le_bytes()) // brightness_max
-//             .read(&0_u32.to_le_bytes()) // colors_min
-//             .read(&256_u32.to_le_bytes()) // colors_max
-//             .read(&51_u32.to_le_bytes()) // speed
-//             .read(&512_u32.to_le_bytes()) // brightness
-//             .read(&4_u32.to_le_bytes()) // direction
-//             .read(&1_u32.to_le_bytes()) // color_mode
-//             .read(&2_u16.to_le_bytes()) // colors len
-//             .read(&[37_u8, 54_u8, 126_u8, 0_u8]) // colors[0]
-//             .read(&[37_u8, 54_u8, 255_u8, 0_u8]) // colors[1]
-//             .build();
-
-//         assert_eq!(
-//             stream.read_value::<Mode>(DEFAULT_PROTOCOL).await?,
-//             Mode {
-//                 name: "test".to_string(),
-//                 value: 46,
-//                 flags: HasDirection | HasSpeed | HasBrightness,
-//                 speed_min: Some(10),
-//                 speed_max: Some(1000),
-//                 brightness_min: Some(1),
-//                 brightness_max: Some(1024),
-//                 colors_min: Some(0),
-//                 colors_max: Some(256),
-//                 speed: Some(51),
-//                 brightness: Some(512),
-//                 direction: Some(Direction::Horizontal),
-//                 color_mode: Some(ColorMode::PerLED),
-//                 colors: vec![
-//                     Color {
-//                         r: 37,
-//                         g: 54,
-//                         b: 126
-//                     },
-//                     Color {
-//                         r: 37,
-//                         g: 54,
-//                         b: 255
-//                     },
-//                 ],
-//             }
-//         );
-
-//         Ok(())
-//     }
-
-//     #[tokio::test]
-//     async fn test_read_002() -> Result<(), Box<dyn Error>> {
-//         setup()?;
-
-//         let mut stream = Builder::new()
-//             .read(&5_u16.to_le_bytes()) // name len
-//             .read(b"test\0") // name
-//             .read(&46_i32.to_le_bytes()) // value
-//             .read(&0_u32.to_le_bytes()) // flags
-//             .read(&10_u32.to_le_bytes()) // speed_min
-//             .read(&1000_u32.to_le_bytes()) // speed_max
-//             .read(&1_u32.to_le_bytes()) // brightness_min
-//             .read(&1024_u32.to_le_bytes()) // brightness_max
-//             .read(&0_u32.to_le_bytes()) // colors_min
-//             .read(&256_u32.to_le_bytes()) // colors_max
-//             .read(&51_u32.to_le_bytes()) // speed
-//             .read(&512_u32.to_le_bytes()) // brightness
-//             .read(&4_u32.to_le_bytes()) // direction
-//             .read(&1_u32.to_le_bytes()) // color_mode
-//             .read(&0_u16.to_le_bytes()) // colors len
-//             .build();
-
-//         assert_eq!(
-//             stream.read_value::<Mode>(DEFAULT_PROTOCOL).await?,
-//             Mode {
-//                 name: "test".to_string(),
-//                 value: 46,
-//                 flags: Default::default(),
-//                 speed_min: None,
-//                 speed_max: None,
-//                 brightness_min: None,
-//                 brightness_max: None,
-//                 colors_min: None,
-//                 colors_max: None,
-//                 speed: None,
-//                 brightness: None,
-//                 direction: None,
-//                 color_mode: Some(ColorMode::PerLED),
-//                 colors: vec![],
-//             }
-//         );
-
-//         Ok(())
-//     }
-
-//     #[tokio::test]
-//     async fn test_read_003() -> Result<(), Box<dyn Error>> {
-//         setup()?;
-
-//         let mut stream = Builder::new()
-//             .read(&5_u16.to_le_bytes()) // name len
-//             .read(b"test\0") // name
-//             .read(&46_i32.to_le_bytes()) // value
-//             .read(&31_u32.to_le_bytes()) // flags
-//             .read(&10_u32.to_le_bytes()) // speed_min
-//             .read(&1000_u32.to_le_bytes()) // speed_max
-//             .read(&0_u32.to_le_bytes()) // colors_min
-//             .read(&256_u32.to_le_bytes()) // colors_max
-//             .read(&51_u32.to_le_bytes()) // speed
-//             .read(&4_u32.to_le_bytes()) // direction
-//             .read(&1_u32.to_le_bytes()) // color_mode
-//             .read(&2_u16.to_le_bytes()) // colors len
-//             .read(&[37_u8, 54_u8, 126_u8, 0_u8]) // colors[0]
-//             .read(&[37_u8, 54_u8, 255_u8, 0_u8]) // colors[1]
-//             .build();
-
-//         assert_eq!(
-//             stream.read_value::<Mode>(2).await?,
-//             Mode {
-//                 name: "test".to_string(),
-//                 value: 46,
-//                 flags: HasDirection | HasSpeed | HasBrightness,
-//                 speed_min: Some(10),
-//                 speed_max: Some(1000),
-//                 brightness_min: None,
-//                 brightness_max: None,
-//                 colors_min: Some(0),
-//                 colors_max: Some(256),
-//                 speed: Some(51),
-//                 brightness: None,
-//                 direction: Some(Direction::Horizontal),
-//                 color_mode: Some(ColorMode::PerLED),
-//                 colors: vec![
-//                     Color {
-//                         r: 37,
-//                         g: 54,
-//                         b: 126
-//                     },
-//                     Color {
-//                         r: 37,
-//                         g: 54,
-//                         b: 255
-//                     },
-//                 ],
-//             }
-//         );
-
-//         Ok(())
-//     }
-
-//     #[tokio::test]
-//     async fn test_write_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
-
-//         let mut stream = Builder::new()
-//             .write(&5_u16.to_le_bytes()) // name len
-//             .write(b"test\0") // name
-//             .write(&46_i32.to_le_bytes()) // value
-//             .write(&31_u32.to_le_bytes()) // flags
-//             .write(&10_u32.to_le_bytes()) // speed_min
-//             .write(&1000_u32.to_le_bytes()) // speed_max
-//             .write(&1_u32.to_le_bytes()) // brightness_min
-//             .write(&1024_u32.to_le_bytes()) // brightness_max
-//             .write(&0_u32.to_le_bytes()) // colors_min
-//             .write(&256_u32.to_le_bytes()) // colors_max
-//             .write(&51_u32.to_le_bytes()) // speed
-//             .write(&512_u32.to_le_bytes()) // brightness
-//             .write(&4_u32.to_le_bytes()) // direction
-//             .write(&1_u32.to_le_bytes()) // color_mode
-//             .write(&2_u16.to_le_bytes()) // colors len
-//             .write(&[37_u8, 54_u8, 126_u8, 0_u8]) // colors[0]
-//             .write(&[37_u8, 54_u8, 255_u8, 0_u8]) // colors[1]
-//             .build();
-
-//         stream
-//             .write_value(
-//                 Mode {
-//                     name: "test".to_string(),
-//                     value: 46,
-//                     flags: HasDirection | HasSpeed | HasBrightness,
-//                     speed_min: Some(10),
-//                     speed_max: Some(1000),
-//                     brightness_min: Some(1),
-//                     brightness_max: Some(1024),
-//                     colors_min: Some(0),
-//                     colors_max: Some(256),
-//                     speed: Some(51),
-//                     brightness: Some(512),
-//                     direction: Some(Direction::Horizontal),
-//                     color_mode: Some(ColorMode::PerLED),
-//                     colors: vec![
-//                         Color {
-//                             r: 37,
-//                             g: 54,
-//                             b: 126,
-//                         },
-//                         Color {
-//                             r: 37,
-//                             g: 54,
-//                             b: 255,
-//                         },
-//                     ],
-//                 },
-//                 DEFAULT_PROTOCOL,
-//             )
-//             .await?;
-
-//         Ok(())
-//     }
-// }
+#[cfg(test)]
+mod tests {
+    use crate::tests::Builder;
+    use crate::OpenRGBError;
+
+    use crate::protocol::{OpenRGBReadableSync, OpenRGBWritableSync};
+    use crate::DEFAULT_PROTOCOL;
+    use crate::{Color, ColorMode, Direction, Mode, ModeFlag::*};
+
+    #[test]
+    fn test_read_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new()
+            .read(&5_u16.to_le_bytes()) // name len
+            .read(b"test\0") // name
+            .read(&46_i32.to_le_bytes()) // value
+            .read(&31_u32.to_le_bytes()) // flags
+            .read(&10_u32.to_le_bytes()) // speed_min
+            .read(&1000_u32.to_le_bytes()) // speed_max
+            .read(&1_u32.to_le_bytes()) // brightness_min
+            .read(&1024_u32.to_le_bytes()) // brightness_max
+            .read(&0_u32.to_le_bytes()) // colors_min
+            .read(&256_u32.to_le_bytes()) // colors_max
+            .read(&51_u32.to_le_bytes()) // speed
+            .read(&512_u32.to_le_bytes()) // brightness
+            .read(&4_u32.to_le_bytes()) // direction
+            .read(&1_u32.to_le_bytes()) // color_mode
+            .read(&2_u16.to_le_bytes()) // colors len
+            .read(&[37_u8, 54_u8, 126_u8, 0_u8]) // colors[0]
+            .read(&[37_u8, 54_u8, 255_u8, 0_u8]) // colors[1]
+            .build();
+
+        assert_eq!(
+            stream.read_value::<Mode>(DEFAULT_PROTOCOL)?,
+            Mode {
+                name: "test".to_string(),
+                value: 46,
+                flags: HasDirection | HasSpeed | HasBrightness,
+                speed_min: Some(10),
+                speed_max: Some(1000),
+                brightness_min: Some(1),
+                brightness_max: Some(1024),
+                colors_min: Some(0),
+                colors_max: Some(256),
+                speed: Some(51),
+                brightness: Some(512),
+                direction: Some(Direction::Horizontal),
+                color_mode: Some(ColorMode::PerLED),
+                colors: vec![
+                    Color {
+                        r: 37,
+                        g: 54,
+                        b: 126
+                    },
+                    Color {
+                        r: 37,
+                        g: 54,
+                        b: 255
+                    },
+                ],
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_002() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new()
+            .read(&5_u16.to_le_bytes()) // name len
+            .read(b"test\0") // name
+            .read(&46_i32.to_le_bytes()) // value
+            .read(&0_u32.to_le_bytes()) // flags
+            .read(&10_u32.to_le_bytes()) // speed_min
+            .read(&1000_u32.to_le_bytes()) // speed_max
+            .read(&1_u32.to_le_bytes()) // brightness_min
+            .read(&1024_u32.to_le_bytes()) // brightness_max
+            .read(&0_u32.to_le_bytes()) // colors_min
+            .read(&256_u32.to_le_bytes()) // colors_max
+            .read(&51_u32.to_le_bytes()) // speed
+            .read(&512_u32.to_le_bytes()) // brightness
+            .read(&4_u32.to_le_bytes()) // direction
+            .read(&1_u32.to_le_bytes()) // color_mode
+            .read(&0_u16.to_le_bytes()) // colors len
+            .build();
+
+        assert_eq!(
+            stream.read_value::<Mode>(DEFAULT_PROTOCOL)?,
+            Mode {
+                name: "test".to_string(),
+                value: 46,
+                flags: Default::default(),
+                speed_min: None,
+                speed_max: None,
+                brightness_min: None,
+                brightness_max: None,
+                colors_min: None,
+                colors_max: None,
+                speed: None,
+                brightness: None,
+                direction: None,
+                color_mode: Some(ColorMode::PerLED),
+                colors: vec![],
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_003() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new()
+            .read(&5_u16.to_le_bytes()) // name len
+            .read(b"test\0") // name
+            .read(&46_i32.to_le_bytes()) // value
+            .read(&31_u32.to_le_bytes()) // flags
+            .read(&10_u32.to_le_bytes()) // speed_min
+            .read(&1000_u32.to_le_bytes()) // speed_max
+            .read(&0_u32.to_le_bytes()) // colors_min
+            .read(&256_u32.to_le_bytes()) // colors_max
+            .read(&51_u32.to_le_bytes()) // speed
+            .read(&4_u32.to_le_bytes()) // direction
+            .read(&1_u32.to_le_bytes()) // color_mode
+            .read(&2_u16.to_le_bytes()) // colors len
+            .read(&[37_u8, 54_u8, 126_u8, 0_u8]) // colors[0]
+            .read(&[37_u8, 54_u8, 255_u8, 0_u8]) // colors[1]
+            .build();
+
+        assert_eq!(
+            stream.read_value::<Mode>(2)?,
+            Mode {
+                name: "test".to_string(),
+                value: 46,
+                flags: HasDirection | HasSpeed | HasBrightness,
+                speed_min: Some(10),
+                speed_max: Some(1000),
+                brightness_min: None,
+                brightness_max: None,
+                colors_min: Some(0),
+                colors_max: Some(256),
+                speed: Some(51),
+                brightness: None,
+                direction: Some(Direction::Horizontal),
+                color_mode: Some(ColorMode::PerLED),
+                colors: vec![
+                    Color {
+                        r: 37,
+                        g: 54,
+                        b: 126
+                    },
+                    Color {
+                        r: 37,
+                        g: 54,
+                        b: 255
+                    },
+                ],
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new()
+            .write(&5_u16.to_le_bytes()) // name len
+            .write(b"test\0") // name
+            .write(&46_i32.to_le_bytes()) // value
+            .write(&31_u32.to_le_bytes()) // flags
+            .write(&10_u32.to_le_bytes()) // speed_min
+            .write(&1000_u32.to_le_bytes()) // speed_max
+            .write(&1_u32.to_le_bytes()) // brightness_min
+            .write(&1024_u32.to_le_bytes()) // brightness_max
+            .write(&0_u32.to_le_bytes()) // colors_min
+            .write(&256_u32.to_le_bytes()) // colors_max
+            .write(&51_u32.to_le_bytes()) // speed
+            .write(&512_u32.to_le_bytes()) // brightness
+            .write(&4_u32.to_le_bytes()) // direction
+            .write(&1_u32.to_le_bytes()) // color_mode
+            .write(&2_u16.to_le_bytes()) // colors len
+            .write(&[37_u8, 54_u8, 126_u8, 0_u8]) // colors[0]
+            .write(&[37_u8, 54_u8, 255_u8, 0_u8]) // colors[1]
+            .build();
+
+        stream.write_value(
+            Mode {
+                name: "test".to_string(),
+                value: 46,
+                flags: HasDirection | HasSpeed | HasBrightness,
+                speed_min: Some(10),
+                speed_max: Some(1000),
+                brightness_min: Some(1),
+                brightness_max: Some(1024),
+                colors_min: Some(0),
+                colors_max: Some(256),
+                speed: Some(51),
+                brightness: Some(512),
+                direction: Some(Direction::Horizontal),
+                color_mode: Some(ColorMode::PerLED),
+                colors: vec![
+                    Color {
+                        r: 37,
+                        g: 54,
+                        b: 126,
+                    },
+                    Color {
+                        r: 37,
+                        g: 54,
+                        b: 255,
+                    },
+                ],
+            },
+            DEFAULT_PROTOCOL,
+        )?;
+
+        Ok(())
+    }
+}
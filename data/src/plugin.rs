@@ -0,0 +1,124 @@
+use alloc::string::String;
+
+use crate::protocol::{OpenRGBReadableSync, OpenRGBWritableSync};
+use crate::OpenRGBError;
+use crate::{OpenRGBReadable, OpenRGBWritable};
+
+/// SDK plugin loaded by OpenRGB server.
+///
+/// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_request_plugin_list) for more information.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Plugin {
+    /// Plugin name.
+    pub name: String,
+
+    /// Plugin description.
+    pub description: String,
+
+    /// Plugin version.
+    pub version: String,
+
+    /// Plugin index, used as plugin ID in plugin specific requests.
+    pub index: u32,
+
+    /// Plugin SDK protocol version.
+    pub protocol_version: u32,
+}
+
+impl OpenRGBReadable for Plugin {
+    fn read(stream: &mut impl OpenRGBReadableSync, protocol: u32) -> Result<Self, OpenRGBError> {
+        Ok(Plugin {
+            name: stream.read_value(protocol)?,
+            description: stream.read_value(protocol)?,
+            version: stream.read_value(protocol)?,
+            index: stream.read_value(protocol)?,
+            protocol_version: stream.read_value(protocol)?,
+        })
+    }
+}
+
+impl OpenRGBWritable for Plugin {
+    fn size(&self, protocol: u32) -> usize {
+        self.name.size(protocol)
+            + self.description.size(protocol)
+            + self.version.size(protocol)
+            + self.index.size(protocol)
+            + self.protocol_version.size(protocol)
+    }
+
+    fn write(
+        self,
+        stream: &mut impl OpenRGBWritableSync,
+        protocol: u32,
+    ) -> Result<(), OpenRGBError> {
+        stream.write_value(self.name, protocol)?;
+        stream.write_value(self.description, protocol)?;
+        stream.write_value(self.version, protocol)?;
+        stream.write_value(self.index, protocol)?;
+        stream.write_value(self.protocol_version, protocol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::Builder;
+    use crate::OpenRGBError;
+
+    use crate::protocol::{OpenRGBReadableSync, OpenRGBWritableSync};
+    use crate::Plugin;
+    use crate::DEFAULT_PROTOCOL;
+
+    #[test]
+    fn test_read_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new()
+            .read(&8_u16.to_le_bytes()) // name len
+            .read(b"Effects\0") // name
+            .read(&5_u16.to_le_bytes()) // description len
+            .read(b"test\0") // description
+            .read(&4_u16.to_le_bytes()) // version len
+            .read(b"1.0\0") // version
+            .read(&2_u32.to_le_bytes()) // index
+            .read(&1_u32.to_le_bytes()) // protocol version
+            .build();
+
+        assert_eq!(
+            stream.read_value::<Plugin>(DEFAULT_PROTOCOL)?,
+            Plugin {
+                name: "Effects".to_string(),
+                description: "test".to_string(),
+                version: "1.0".to_string(),
+                index: 2,
+                protocol_version: 1,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new()
+            .write(&8_u16.to_le_bytes()) // name len
+            .write(b"Effects\0") // name
+            .write(&5_u16.to_le_bytes()) // description len
+            .write(b"test\0") // description
+            .write(&4_u16.to_le_bytes()) // version len
+            .write(b"1.0\0") // version
+            .write(&2_u32.to_le_bytes()) // index
+            .write(&1_u32.to_le_bytes()) // protocol version
+            .build();
+
+        stream.write_value(
+            Plugin {
+                name: "Effects".to_string(),
+                description: "test".to_string(),
+                version: "1.0".to_string(),
+                index: 2,
+                protocol_version: 1,
+            },
+            DEFAULT_PROTOCOL,
+        )?;
+
+        Ok(())
+    }
+}
@@ -44,7 +44,7 @@ impl OpenRGBWritable for u8 {
 
 impl OpenRGBReadable for u8 {
     fn read(stream: &mut impl OpenRGBReadableSync, _protocol: u32) -> Result<Self, OpenRGBError> {
-        stream.read_u8().map_err(Into::into)
+        stream.read_u8()
     }
 }
 
@@ -68,9 +68,7 @@ impl OpenRGBWritable for u16 {
 impl OpenRGBReadable for u16 {
     fn read(stream: &mut impl OpenRGBReadableSync, _protocol: u32) -> Result<Self, OpenRGBError> {
         let mut buf = [0u8; 2];
-        stream
-            .read_exact(&mut buf)
-            .map_err(|_| OpenRGBError::CommunicationError(format!("failed reading u16")))?;
+        stream.read_bytes(&mut buf)?;
         Ok(u16::from_le_bytes(buf))
     }
 }
@@ -95,9 +93,7 @@ impl OpenRGBWritable for u32 {
 impl OpenRGBReadable for u32 {
     fn read(stream: &mut impl OpenRGBReadableSync, _protocol: u32) -> Result<Self, OpenRGBError> {
         let mut buf = [0u8; 4];
-        stream
-            .read_exact(&mut buf)
-            .map_err(|_| OpenRGBError::CommunicationError(format!("failed reading u32")))?;
+        stream.read_bytes(&mut buf)?;
         Ok(u32::from_le_bytes(buf))
     }
 }
@@ -122,9 +118,7 @@ impl OpenRGBWritable for i32 {
 impl OpenRGBReadable for i32 {
     fn read(stream: &mut impl OpenRGBReadableSync, _protocol: u32) -> Result<Self, OpenRGBError> {
         let mut buf = [0u8; 4];
-        stream
-            .read_exact(&mut buf)
-            .map_err(|_| OpenRGBError::CommunicationError(format!("failed reading i32")))?;
+        stream.read_bytes(&mut buf)?;
         Ok(i32::from_le_bytes(buf))
     }
 }
@@ -152,165 +146,119 @@ impl OpenRGBReadable for usize {
     }
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use std::error::Error;
-
-//     use tokio_test::io::Builder;
-
-//     use crate::{DEFAULT_PROTOCOL};
-//     use crate::protocol::{OpenRGBReadableStream, OpenRGBWritableStream};
-//     use crate::tests::setup;
-
-//     #[tokio::test]
-//     async fn test_read_void_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
-
-//         let mut stream = Builder::new().build();
-
-//         assert_eq!(stream.read_value::<()>(DEFAULT_PROTOCOL).await?, ());
-
-//         Ok(())
-//     }
-
-//     #[tokio::test]
-//     async fn test_write_void_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
-
-//         let mut stream = Builder::new().build();
+#[cfg(test)]
+mod tests {
+    use crate::tests::Builder;
+    use crate::OpenRGBError;
 
-//         stream.write_value((), DEFAULT_PROTOCOL).await?;
+    use crate::protocol::{OpenRGBReadableSync, OpenRGBWritableSync};
+    use crate::DEFAULT_PROTOCOL;
 
-//         Ok(())
-//     }
+    #[test]
+    fn test_read_void_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new().build();
 
-//     #[tokio::test]
-//     async fn test_read_u8_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
+        assert_eq!(stream.read_value::<()>(DEFAULT_PROTOCOL)?, ());
 
-//         let mut stream = Builder::new()
-//             .read(&[37_u8])
-//             .build();
-
-//         assert_eq!(stream.read_value::<u8>(DEFAULT_PROTOCOL).await?, 37);
-
-//         Ok(())
-//     }
-
-//     #[tokio::test]
-//     async fn test_write_u8_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
-
-//         let mut stream = Builder::new()
-//             .write(&[37_u8])
-//             .build();
-
-//         stream.write_value(37_u8, DEFAULT_PROTOCOL).await?;
+        Ok(())
+    }
 
-//         Ok(())
-//     }
+    #[test]
+    fn test_write_void_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new().build();
 
-//     #[tokio::test]
-//     async fn test_read_u16_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
+        stream.write_value((), DEFAULT_PROTOCOL)?;
 
-//         let mut stream = Builder::new()
-//             .read(&37_u16.to_le_bytes())
-//             .build();
+        Ok(())
+    }
 
-//         assert_eq!(stream.read_value::<u16>(DEFAULT_PROTOCOL).await?, 37);
+    #[test]
+    fn test_read_u8_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new().read(&[37_u8]).build();
 
-//         Ok(())
-//     }
+        assert_eq!(stream.read_value::<u8>(DEFAULT_PROTOCOL)?, 37);
 
-//     #[tokio::test]
-//     async fn test_write_u16_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
+        Ok(())
+    }
 
-//         let mut stream = Builder::new()
-//             .write(&37_u16.to_le_bytes())
-//             .build();
+    #[test]
+    fn test_write_u8_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new().write(&[37_u8]).build();
 
-//         stream.write_value(37_u16, DEFAULT_PROTOCOL).await?;
+        stream.write_value(37_u8, DEFAULT_PROTOCOL)?;
 
-//         Ok(())
-//     }
+        Ok(())
+    }
 
-//     #[tokio::test]
-//     async fn test_read_u32_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
+    #[test]
+    fn test_read_u16_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new().read(&37_u16.to_le_bytes()).build();
 
-//         let mut stream = Builder::new()
-//             .read(&185851_u32.to_le_bytes())
-//             .build();
+        assert_eq!(stream.read_value::<u16>(DEFAULT_PROTOCOL)?, 37);
 
-//         assert_eq!(stream.read_value::<u32>(DEFAULT_PROTOCOL).await?, 185851);
+        Ok(())
+    }
 
-//         Ok(())
-//     }
+    #[test]
+    fn test_write_u16_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new().write(&37_u16.to_le_bytes()).build();
 
-//     #[tokio::test]
-//     async fn test_write_u32_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
+        stream.write_value(37_u16, DEFAULT_PROTOCOL)?;
 
-//         let mut stream = Builder::new()
-//             .write(&185851_u32.to_le_bytes())
-//             .build();
+        Ok(())
+    }
 
-//         stream.write_value(185851_u32, DEFAULT_PROTOCOL).await?;
+    #[test]
+    fn test_read_u32_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new().read(&185851_u32.to_le_bytes()).build();
 
-//         Ok(())
-//     }
+        assert_eq!(stream.read_value::<u32>(DEFAULT_PROTOCOL)?, 185851);
 
-//     #[tokio::test]
-//     async fn test_read_i32_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
+        Ok(())
+    }
 
-//         let mut stream = Builder::new()
-//             .read(&(-185851_i32).to_le_bytes())
-//             .build();
+    #[test]
+    fn test_write_u32_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new().write(&185851_u32.to_le_bytes()).build();
 
-//         assert_eq!(stream.read_value::<i32>(DEFAULT_PROTOCOL).await?, -185851_i32);
+        stream.write_value(185851_u32, DEFAULT_PROTOCOL)?;
 
-//         Ok(())
-//     }
+        Ok(())
+    }
 
-//     #[tokio::test]
-//     async fn test_write_i32_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
+    #[test]
+    fn test_read_i32_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new().read(&(-185851_i32).to_le_bytes()).build();
 
-//         let mut stream = Builder::new()
-//             .write(&(-185851_i32).to_le_bytes())
-//             .build();
+        assert_eq!(stream.read_value::<i32>(DEFAULT_PROTOCOL)?, -185851_i32);
 
-//         stream.write_value(-185851_i32, DEFAULT_PROTOCOL).await?;
+        Ok(())
+    }
 
-//         Ok(())
-//     }
+    #[test]
+    fn test_write_i32_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new().write(&(-185851_i32).to_le_bytes()).build();
 
-//     #[tokio::test]
-//     async fn test_read_usize_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
+        stream.write_value(-185851_i32, DEFAULT_PROTOCOL)?;
 
-//         let mut stream = Builder::new()
-//             .read(&185851_u32.to_le_bytes())
-//             .build();
+        Ok(())
+    }
 
-//         assert_eq!(stream.read_value::<usize>(DEFAULT_PROTOCOL).await?, 185851_usize);
+    #[test]
+    fn test_read_usize_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new().read(&185851_u32.to_le_bytes()).build();
 
-//         Ok(())
-//     }
+        assert_eq!(stream.read_value::<usize>(DEFAULT_PROTOCOL)?, 185851_usize);
 
-//     #[tokio::test]
-//     async fn test_write_usize_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
+        Ok(())
+    }
 
-//         let mut stream = Builder::new()
-//             .write(&185851_u32.to_le_bytes())
-//             .build();
+    #[test]
+    fn test_write_usize_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new().write(&185851_u32.to_le_bytes()).build();
 
-//         stream.write_value(185851_usize, DEFAULT_PROTOCOL).await?;
+        stream.write_value(185851_usize, DEFAULT_PROTOCOL)?;
 
-//         Ok(())
-//     }
-// }
+        Ok(())
+    }
+}
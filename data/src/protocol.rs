@@ -1,4 +1,4 @@
-use crate::{OpenRGBError, OpenRGBReadable, OpenRGBWritable, PacketId};
+use crate::{DecodeLimits, OpenRGBError, OpenRGBReadable, OpenRGBWritable, PacketId};
 use alloc::fmt::{format, Debug};
 use alloc::format;
 use alloc::vec::Vec;
@@ -6,7 +6,10 @@ use genio::{Read, Write};
 
 static MAGIC: [u8; 4] = *b"ORGB";
 
-#[derive(Debug, Default)]
+/// Default protocol version used by OpenRGB clients.
+pub static DEFAULT_PROTOCOL: u32 = 4;
+
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
 pub struct Header {
     pub magic: [u8; 4],
     pub device_id: u32,
@@ -17,9 +20,7 @@ pub struct Header {
 impl OpenRGBReadable for Header {
     fn read(stream: &mut impl OpenRGBReadableSync, protocol: u32) -> Result<Self, OpenRGBError> {
         let mut h = Header::default();
-        stream
-            .read_exact(&mut h.magic)
-            .map_err(|_| OpenRGBError::ProtocolError(format!("read error for magic")))?;
+        stream.read_bytes(&mut h.magic)?;
         for (i, c) in h.magic.iter().enumerate() {
             if *c != MAGIC[i] {
                 return Err(OpenRGBError::BadMagic(*c));
@@ -58,18 +59,50 @@ impl OpenRGBWritable for Header {
     }
 }
 
-pub trait OpenRGBReadableSync: Read + Sized {
+/// Maximum number of items preallocated for a list, larger lists grow as items are read, so that
+/// a length prefix alone cannot make decoding allocate more than received data.
+const MAX_PREALLOCATED_ITEMS: usize = 0x400;
+
+pub trait OpenRGBReadableSync: Sized {
+    /// Fill given buffer with the next bytes of stream.
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), OpenRGBError>;
+
+    /// Limits applied to lengths read from this stream.
+    fn limits(&self) -> &DecodeLimits {
+        &DecodeLimits::DEFAULT
+    }
+
     fn read_value<T: OpenRGBReadable>(&mut self, protocol: u32) -> Result<T, OpenRGBError> {
         T::read(self, protocol)
     }
 
     fn read_u8(&mut self) -> Result<u8, OpenRGBError> {
         let mut buf = [0u8; 1];
-        self.read(&mut buf)
-            .map_err(|_| OpenRGBError::CommunicationError(format!("failed reading u8")))?;
+        self.read_bytes(&mut buf)?;
         Ok(buf[0])
     }
 
+    /// Read a `u16` length prefix, and check it does not exceed given limit.
+    fn read_len(&mut self, protocol: u32, max: usize, what: &str) -> Result<usize, OpenRGBError> {
+        let len = self.read_value::<u16>(protocol)?;
+        DecodeLimits::check(len.into(), max, what)
+    }
+
+    /// Read a length prefixed list of at most `max` values.
+    fn read_list<T: OpenRGBReadable>(
+        &mut self,
+        protocol: u32,
+        max: usize,
+        what: &str,
+    ) -> Result<Vec<T>, OpenRGBError> {
+        let len = self.read_len(protocol, max, what)?;
+        let mut vec = Vec::with_capacity(len.min(MAX_PREALLOCATED_ITEMS));
+        for _ in 0..len {
+            vec.push(self.read_value(protocol)?);
+        }
+        Ok(vec)
+    }
+
     fn read_any(&mut self, protocol: u32) -> Result<Header, OpenRGBError> {
         Ok(self.read_value(protocol)?)
     }
@@ -81,8 +114,7 @@ pub trait OpenRGBReadableSync: Read + Sized {
         expected_packet_id: PacketId,
     ) -> Result<usize, OpenRGBError> {
         let mut buf = [0u8; 4];
-        self.read_exact(&mut buf[0..4])
-            .map_err(|_| OpenRGBError::ProtocolError(format!("read error for magic")))?;
+        self.read_bytes(&mut buf)?;
         for (i, c) in buf.iter().enumerate() {
             if *c != MAGIC[i] {
                 return Err(OpenRGBError::BadMagic(*c));
@@ -100,14 +132,16 @@ pub trait OpenRGBReadableSync: Read + Sized {
         let packet_id = self.read_value::<PacketId>(protocol)?;
         if packet_id != expected_packet_id {
             return Err(OpenRGBError::PacketIDMismatch {
-                expected: expected_packet_id as u32,
-                got: packet_id as u32,
+                expected: expected_packet_id.into(),
+                got: packet_id.into(),
             });
         }
 
-        self.read_value::<u32>(protocol)?
+        let len = self
+            .read_value::<u32>(protocol)?
             .try_into()
-            .map_err(|_| OpenRGBError::CommunicationError(format!("failed reading packet size")))
+            .map_err(|_| OpenRGBError::CommunicationError(format!("failed reading packet size")))?;
+        DecodeLimits::check(len, self.limits().max_packet_size, "packet")
     }
 
     fn read_packet<O: OpenRGBReadable>(
@@ -116,14 +150,144 @@ pub trait OpenRGBReadableSync: Read + Sized {
         expected_device_id: u32,
         expected_packet_id: PacketId,
     ) -> Result<O, OpenRGBError> {
-        self.read_header(protocol, expected_device_id, expected_packet_id)?;
-        // TODO check header length vs actual read length
-        self.read_value(protocol)
+        let len = self.read_header(protocol, expected_device_id, expected_packet_id)?;
+        self.read_bounded(len, protocol)
+    }
+
+    /// Read value from the next `len` bytes.
+    ///
+    /// Fails if value is longer than `len` bytes, and skips remaining bytes if it is shorter, so
+    /// that fields appended by newer protocol versions are ignored.
+    fn read_bounded<T: OpenRGBReadable>(
+        &mut self,
+        len: usize,
+        protocol: u32,
+    ) -> Result<T, OpenRGBError> {
+        self.read_bounded_with(len, |stream| stream.read_value(protocol))
+    }
+
+    /// Read from the next `len` bytes with given function, see [OpenRGBReadableSync::read_bounded].
+    fn read_bounded_with<T>(
+        &mut self,
+        len: usize,
+        read: impl FnOnce(&mut Bounded<Self>) -> Result<T, OpenRGBError>,
+    ) -> Result<T, OpenRGBError> {
+        let mut bounded = Bounded {
+            stream: self,
+            remaining: len,
+            overread: false,
+        };
+        let result = read(&mut bounded);
+        if bounded.overread {
+            return Err(OpenRGBError::ProtocolError(format!(
+                "read past end of {} bytes data",
+                len
+            )));
+        }
+        let value = result?;
+        bounded.skip_remaining()?;
+        Ok(value)
     }
 }
 
-// impl OpenRGBReadableSync for &[u8] {}
-impl<T: Read> OpenRGBReadableSync for T {}
+/// Reader limited to a given number of bytes, see [OpenRGBReadableSync::read_bounded].
+pub struct Bounded<'a, R> {
+    stream: &'a mut R,
+    remaining: usize,
+    overread: bool,
+}
+
+impl<R: OpenRGBReadableSync> Bounded<'_, R> {
+    fn skip_remaining(&mut self) -> Result<(), OpenRGBError> {
+        let mut buf = [0u8; 64];
+        while self.remaining > 0 {
+            let len = self.remaining.min(buf.len());
+            self.read_bytes(&mut buf[..len])?;
+        }
+        Ok(())
+    }
+}
+
+impl<R: OpenRGBReadableSync> OpenRGBReadableSync for Bounded<'_, R> {
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), OpenRGBError> {
+        if buf.len() > self.remaining {
+            // consume remaining bytes anyway, so that stream stays at end of data
+            self.overread = true;
+            self.stream.read_bytes(&mut buf[..self.remaining])?;
+            self.remaining = 0;
+            return Err(OpenRGBError::ProtocolError("read past end of data".into()));
+        }
+        self.stream.read_bytes(buf)?;
+        self.remaining -= buf.len();
+        Ok(())
+    }
+
+    fn limits(&self) -> &DecodeLimits {
+        self.stream.limits()
+    }
+}
+
+/// Stream applying given [DecodeLimits] to lengths read from it.
+pub struct Limited<S> {
+    stream: S,
+    limits: DecodeLimits,
+}
+
+impl<S> Limited<S> {
+    /// Wrap given stream, applying given limits.
+    pub fn new(stream: S, limits: DecodeLimits) -> Self {
+        Self { stream, limits }
+    }
+
+    /// Set limits applied to lengths read from stream.
+    pub fn set_limits(&mut self, limits: DecodeLimits) {
+        self.limits = limits;
+    }
+
+    /// Unwrap underlying stream.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S: Read> OpenRGBReadableSync for Limited<S> {
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), OpenRGBError> {
+        self.stream.read_bytes(buf)
+    }
+
+    fn limits(&self) -> &DecodeLimits {
+        &self.limits
+    }
+}
+
+impl<S: Write> Write for Limited<S> {
+    type WriteError = S::WriteError;
+
+    type FlushError = S::FlushError;
+
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::WriteError> {
+        self.stream.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::FlushError> {
+        self.stream.flush()
+    }
+
+    fn size_hint(&mut self, bytes: usize) {
+        self.stream.size_hint(bytes)
+    }
+
+    fn uses_size_hint(&self) -> bool {
+        self.stream.uses_size_hint()
+    }
+}
+
+impl<T: Read> OpenRGBReadableSync for T {
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), OpenRGBError> {
+        self.read_exact(buf)
+            .map_err(|_| OpenRGBError::CommunicationError("failed reading data".into()))
+    }
+}
 
 pub trait OpenRGBWritableSync: Write + Sized {
     fn write_value<T: OpenRGBWritable>(
@@ -189,6 +353,8 @@ pub trait OpenRGBSync: OpenRGBReadableSync + OpenRGBWritableSync {
     }
 }
 
+impl<T: OpenRGBReadableSync + Write> OpenRGBSync for T {}
+
 pub struct WriteVec<'a>(&'a mut Vec<u8>);
 
 impl<'a> WriteVec<'a> {
@@ -200,6 +366,10 @@ impl<'a> WriteVec<'a> {
         self.0.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     pub fn capacity(&self) -> usize {
         self.0.capacity()
     }
@@ -236,3 +406,67 @@ impl<'a> Write for WriteVec<'a> {
 
 // #[cfg(debug_assertions)]
 // impl OpenRGBWritableStream for Vec<u8> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::Builder;
+    use crate::OpenRGBError;
+
+    use crate::protocol::OpenRGBReadableSync;
+    use crate::PacketId::RequestControllerCount;
+    use crate::DEFAULT_PROTOCOL;
+
+    #[test]
+    fn test_read_bounded_skip_trailing_bytes() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new()
+            .read(&37_u32.to_le_bytes()) // value
+            .read(&[1, 2, 3]) // trailing bytes
+            .read(&54_u32.to_le_bytes()) // next value
+            .build();
+
+        assert_eq!(stream.read_bounded::<u32>(7, DEFAULT_PROTOCOL)?, 37);
+        assert_eq!(stream.read_value::<u32>(DEFAULT_PROTOCOL)?, 54);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_bounded_overread() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new().read(&37_u16.to_le_bytes()).build();
+
+        assert!(matches!(
+            stream.read_bounded::<u32>(2, DEFAULT_PROTOCOL),
+            Err(OpenRGBError::ProtocolError(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_packet_skip_trailing_bytes() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new()
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&0_u32.to_le_bytes()) // packet id
+            .read(&8_u32.to_le_bytes()) // data size
+            .read(&7_u32.to_le_bytes()) // count
+            .read(&[1, 2, 3, 4]) // newer protocol field
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&0_u32.to_le_bytes()) // packet id
+            .read(&4_u32.to_le_bytes()) // data size
+            .read(&5_u32.to_le_bytes()) // count
+            .build();
+
+        assert_eq!(
+            stream.read_packet::<u32>(DEFAULT_PROTOCOL, 0, RequestControllerCount)?,
+            7
+        );
+        assert_eq!(
+            stream.read_packet::<u32>(DEFAULT_PROTOCOL, 0, RequestControllerCount)?,
+            5
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use alloc::string::String;
+
+use crate::protocol::{OpenRGBReadableSync, OpenRGBWritableSync};
+use crate::OpenRGBError;
+use crate::{OpenRGBReadable, OpenRGBWritable, ZoneType};
+
+/// RGB controller [Zone](crate::Zone) segment.
+///
+/// Segments split a zone into LED ranges, for example fans daisy-chained on a single header.
+///
+/// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#segment-data) for more information.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Segment {
+    /// Segment name.
+    pub name: String,
+
+    /// Segment type.
+    pub r#type: ZoneType,
+
+    /// Segment first LED index, relative to zone.
+    pub start_idx: u32,
+
+    /// Segment LED count.
+    pub leds_count: u32,
+}
+
+impl OpenRGBReadable for Segment {
+    fn read(stream: &mut impl OpenRGBReadableSync, protocol: u32) -> Result<Self, OpenRGBError> {
+        Ok(Segment {
+            name: stream.read_value(protocol)?,
+            r#type: stream.read_value(protocol)?,
+            start_idx: stream.read_value(protocol)?,
+            leds_count: stream.read_value(protocol)?,
+        })
+    }
+}
+
+impl OpenRGBWritable for Segment {
+    fn size(&self, protocol: u32) -> usize {
+        self.name.size(protocol)
+            + self.r#type.size(protocol)
+            + self.start_idx.size(protocol)
+            + self.leds_count.size(protocol)
+    }
+
+    fn write(
+        self,
+        stream: &mut impl OpenRGBWritableSync,
+        protocol: u32,
+    ) -> Result<(), OpenRGBError> {
+        stream.write_value(self.name, protocol)?;
+        stream.write_value(self.r#type, protocol)?;
+        stream.write_value(self.start_idx, protocol)?;
+        stream.write_value(self.leds_count, protocol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::Builder;
+    use crate::OpenRGBError;
+
+    use crate::protocol::{OpenRGBReadableSync, OpenRGBWritableSync};
+    use crate::DEFAULT_PROTOCOL;
+    use crate::{Segment, ZoneType};
+
+    #[test]
+    fn test_read_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new()
+            .read(&6_u16.to_le_bytes()) // name len
+            .read(b"Fan 1\0") // name
+            .read(&1_u32.to_le_bytes()) // type
+            .read(&12_u32.to_le_bytes()) // start_idx
+            .read(&12_u32.to_le_bytes()) // leds_count
+            .build();
+
+        assert_eq!(
+            stream.read_value::<Segment>(DEFAULT_PROTOCOL)?,
+            Segment {
+                name: "Fan 1".to_string(),
+                r#type: ZoneType::Linear,
+                start_idx: 12,
+                leds_count: 12,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new()
+            .write(&6_u16.to_le_bytes()) // name len
+            .write(b"Fan 1\0") // name
+            .write(&1_u32.to_le_bytes()) // type
+            .write(&12_u32.to_le_bytes()) // start_idx
+            .write(&12_u32.to_le_bytes()) // leds_count
+            .build();
+
+        stream.write_value(
+            Segment {
+                name: "Fan 1".to_string(),
+                r#type: ZoneType::Linear,
+                start_idx: 12,
+                leds_count: 12,
+            },
+            DEFAULT_PROTOCOL,
+        )?;
+
+        Ok(())
+    }
+}
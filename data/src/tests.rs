@@ -0,0 +1,139 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::thread;
+use std::vec::Vec;
+
+use genio::{Read, Write};
+
+use crate::OpenRGBError;
+
+/// Scripted in-memory stream, checking written data and returning read data in order.
+///
+/// This is a blocking equivalent to `tokio_test::io::Mock`.
+pub struct Mock {
+    actions: VecDeque<Action>,
+}
+
+enum Action {
+    Read(Vec<u8>),
+    Write(Vec<u8>),
+}
+
+#[derive(Default)]
+pub struct Builder {
+    actions: VecDeque<Action>,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Expect given data to be read from stream.
+    pub fn read(&mut self, data: &[u8]) -> &mut Self {
+        self.actions.push_back(Action::Read(data.to_vec()));
+        self
+    }
+
+    /// Expect given data to be written to stream.
+    pub fn write(&mut self, data: &[u8]) -> &mut Self {
+        self.actions.push_back(Action::Write(data.to_vec()));
+        self
+    }
+
+    pub fn build(&mut self) -> Mock {
+        Mock {
+            actions: std::mem::take(&mut self.actions),
+        }
+    }
+}
+
+impl Read for Mock {
+    type ReadError = OpenRGBError;
+
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::ReadError> {
+        match self.actions.front_mut() {
+            Some(Action::Read(data)) => {
+                let len = buf.len().min(data.len());
+                buf[..len].copy_from_slice(&data[..len]);
+                data.drain(..len);
+                if data.is_empty() {
+                    self.actions.pop_front();
+                }
+                Ok(len)
+            }
+            _ => Ok(0),
+        }
+    }
+}
+
+impl Write for Mock {
+    type WriteError = OpenRGBError;
+    type FlushError = OpenRGBError;
+
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::WriteError> {
+        match self.actions.front_mut() {
+            Some(Action::Write(data)) => {
+                let len = buf.len().min(data.len());
+                assert_eq!(&buf[..len], &data[..len], "mismatched write");
+                data.drain(..len);
+                if data.is_empty() {
+                    self.actions.pop_front();
+                }
+                Ok(len)
+            }
+            _ => panic!("unexpected write {:?}", buf),
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), Self::FlushError> {
+        Ok(())
+    }
+
+    fn size_hint(&mut self, _bytes: usize) {}
+}
+
+impl Drop for Mock {
+    fn drop(&mut self) {
+        if !thread::panicking() {
+            assert!(self.actions.is_empty(), "actions remaining on mock");
+        }
+    }
+}
+
+/// Allocator tracking memory allocated by each thread, see [peak_allocation].
+struct TrackingAllocator;
+
+thread_local! {
+    static ALLOCATED: Cell<usize> = const { Cell::new(0) };
+    static PEAK: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATED.try_with(|allocated| {
+            allocated.set(allocated.get() + layout.size());
+            let _ = PEAK.try_with(|peak| peak.set(peak.get().max(allocated.get())));
+        });
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // memory may be freed by another thread than the one that allocated it
+        let _ = ALLOCATED
+            .try_with(|allocated| allocated.set(allocated.get().saturating_sub(layout.size())));
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+/// Run given function, and return its result with the peak memory it allocated, in bytes.
+pub fn peak_allocation<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let start = ALLOCATED.with(Cell::get);
+    PEAK.with(|peak| peak.set(start));
+    let result = f();
+    (result, PEAK.with(Cell::get) - start)
+}
@@ -90,56 +90,46 @@ impl<A: OpenRGBReadable, B: OpenRGBReadable, C: OpenRGBReadable, D: OpenRGBReada
     }
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use std::error::Error;
-
-//     use tokio_test::io::Builder;
-
-//     use crate::data::DeviceType;
-//     use crate::protocol::{OpenRGBReadableStream, OpenRGBWritableStream};
-//     use crate::tests::setup;
-//     use crate::DEFAULT_PROTOCOL;
-
-//     #[tokio::test]
-//     async fn test_read_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
-
-//         let mut stream = Builder::new()
-//             .read(&37_u8.to_le_bytes())
-//             .read(&1337_u32.to_le_bytes())
-//             .read(&(-1337_i32).to_le_bytes())
-//             .read(&4_u32.to_le_bytes())
-//             .build();
-
-//         assert_eq!(
-//             stream
-//                 .read_value::<(u8, u32, i32, DeviceType)>(DEFAULT_PROTOCOL)
-//                 .await?,
-//             (37, 1337, -1337, DeviceType::LEDStrip)
-//         );
-
-//         Ok(())
-//     }
-
-//     #[tokio::test]
-//     async fn test_write_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
-
-//         let mut stream = Builder::new()
-//             .write(&37_u8.to_le_bytes())
-//             .write(&1337_u32.to_le_bytes())
-//             .write(&(-1337_i32).to_le_bytes())
-//             .write(&4_u32.to_le_bytes())
-//             .build();
-
-//         stream
-//             .write_value(
-//                 (37_u8, 1337_u32, (-1337_i32), DeviceType::LEDStrip),
-//                 DEFAULT_PROTOCOL,
-//             )
-//             .await?;
-
-//         Ok(())
-//     }
-// }
+#[cfg(test)]
+mod tests {
+    use crate::tests::Builder;
+    use crate::OpenRGBError;
+
+    use crate::protocol::{OpenRGBReadableSync, OpenRGBWritableSync};
+    use crate::DeviceType;
+    use crate::DEFAULT_PROTOCOL;
+
+    #[test]
+    fn test_read_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new()
+            .read(&37_u8.to_le_bytes())
+            .read(&1337_u32.to_le_bytes())
+            .read(&(-1337_i32).to_le_bytes())
+            .read(&4_u32.to_le_bytes())
+            .build();
+
+        assert_eq!(
+            stream.read_value::<(u8, u32, i32, DeviceType)>(DEFAULT_PROTOCOL)?,
+            (37, 1337, -1337, DeviceType::LEDStrip)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new()
+            .write(&37_u8.to_le_bytes())
+            .write(&1337_u32.to_le_bytes())
+            .write(&(-1337_i32).to_le_bytes())
+            .write(&4_u32.to_le_bytes())
+            .build();
+
+        stream.write_value(
+            (37_u8, 1337_u32, (-1337_i32), DeviceType::LEDStrip),
+            DEFAULT_PROTOCOL,
+        )?;
+
+        Ok(())
+    }
+}
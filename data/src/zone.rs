@@ -3,20 +3,67 @@ use alloc::string::String;
 use alloc::vec::Vec;
 
 use crate::protocol::OpenRGBReadableSync;
-use crate::{OpenRGBError, OpenRGBWritable};
-use crate::{OpenRGBReadable, ZoneType};
-
-const MAX_LEDS: usize = 2048;
+use crate::OpenRGBError::InvalidArgument;
+use crate::{DecodeLimits, OpenRGBError, OpenRGBWritable};
+use crate::{OpenRGBReadable, Segment, ZoneType};
 
+/// Zone LED matrix, mapping positions to LED indices.
+///
+/// Positions without LED hold [Matrix::NO_LED].
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct Matrix {
-    len: u16,
+    num_rows: u32,
+    num_columns: u32,
+    data: Vec<u32>,
+}
 
-    height: u32,
+impl Matrix {
+    /// Value of positions without LED.
+    pub const NO_LED: u32 = u32::MAX;
 
-    width: u32,
+    /// Build matrix from values in row major order.
+    ///
+    /// Returns `None` if `data` length does not match dimensions.
+    pub fn from_row_major(data: Vec<u32>, num_rows: u32, num_columns: u32) -> Option<Self> {
+        if (num_rows as usize).checked_mul(num_columns as usize) != Some(data.len()) {
+            return None;
+        }
+        Some(Self {
+            num_rows,
+            num_columns,
+            data,
+        })
+    }
 
-    data: Vec<u32>,
+    /// Number of rows.
+    pub fn num_rows(&self) -> u32 {
+        self.num_rows
+    }
+
+    /// Number of columns.
+    pub fn num_columns(&self) -> u32 {
+        self.num_columns
+    }
+
+    /// Get LED index at given position, if within bounds.
+    pub fn get(&self, row: u32, column: u32) -> Option<u32> {
+        if row >= self.num_rows || column >= self.num_columns {
+            return None;
+        }
+        self.data
+            .get(row as usize * self.num_columns as usize + column as usize)
+            .copied()
+    }
+
+    /// Values in row major order.
+    pub fn as_row_major(&self) -> &[u32] {
+        &self.data
+    }
+
+    /// Wire size of matrix data, excluding length prefix.
+    fn data_size(&self) -> usize {
+        0_u32.size(0) * (2 + self.data.len())
+    }
 }
 
 /// RGB controller zone.
@@ -41,6 +88,30 @@ pub struct Zone {
 
     /// Zone LED matrix (if [Zone::type] is [ZoneType::Matrix]).
     pub matrix: Option<Matrix>,
+
+    /// Zone segments (only with protocol version 4 or higher).
+    pub segments: Vec<Segment>,
+}
+
+impl Zone {
+    /// Check that zone can be resized to given LED count.
+    ///
+    /// Only [ZoneType::Linear] zones can be resized, within [Zone::leds_min] and [Zone::leds_max].
+    pub fn check_resize(&self, new_size: u32) -> Result<(), OpenRGBError> {
+        if self.r#type != ZoneType::Linear {
+            return Err(InvalidArgument(format!(
+                "zone {:?} of type {:?} cannot be resized",
+                self.name, self.r#type
+            )));
+        }
+        if new_size < self.leds_min || new_size > self.leds_max {
+            return Err(InvalidArgument(format!(
+                "zone {:?} size must be between {} and {}, got {}",
+                self.name, self.leds_min, self.leds_max, new_size
+            )));
+        }
+        Ok(())
+    }
 }
 
 impl OpenRGBReadable for Zone {
@@ -49,28 +120,41 @@ impl OpenRGBReadable for Zone {
         let r#type = stream.read_value(protocol)?;
         let leds_min = stream.read_value(protocol)?;
         let leds_max = stream.read_value(protocol)?;
-        let leds_count = stream.read_value(protocol)?;
+        let leds_count = stream.read_value::<u32>(protocol)?;
+        DecodeLimits::check(leds_count as usize, stream.limits().max_leds, "zone LEDs")?;
         let matrix_len = stream.read_value::<u16>(protocol)? as usize;
         let matrix = match matrix_len {
             0 => None,
             _ => Some({
-                let matrix_height = stream.read_value::<u32>(protocol)?;
-                let matrix_width = stream.read_value::<u32>(protocol)?;
-                let matrix_size: u16 = (matrix_height * matrix_width).try_into().map_err(|_| {
-                    OpenRGBError::CommunicationError(format!("failed reading matrix size"))
-                })?;
-                let mut matrix_data = Vec::with_capacity(matrix_size.into());
+                let num_rows = stream.read_value::<u32>(protocol)?;
+                let num_columns = stream.read_value::<u32>(protocol)?;
+                let matrix_size = (num_rows as usize)
+                    .checked_mul(num_columns as usize)
+                    .filter(|size| {
+                        size.checked_add(2).and_then(|n| n.checked_mul(4)) == Some(matrix_len)
+                    })
+                    .ok_or_else(|| {
+                        OpenRGBError::ProtocolError(format!(
+                            "received invalid matrix of {}x{} for {} bytes",
+                            num_rows, num_columns, matrix_len
+                        ))
+                    })?;
+                DecodeLimits::check(matrix_size, stream.limits().max_leds, "zone matrix")?;
+                let mut matrix_data = Vec::with_capacity(matrix_size);
                 for _ in 0..matrix_size {
                     matrix_data.push(stream.read_value(protocol)?);
                 }
                 Matrix {
-                    len: matrix_size,
-                    height: matrix_height,
-                    width: matrix_width,
+                    num_rows,
+                    num_columns,
                     data: matrix_data,
                 }
             }),
         };
+        let segments = match protocol {
+            0..=3 => Vec::new(),
+            _ => stream.read_value(protocol)?,
+        };
         Ok(Zone {
             name,
             r#type,
@@ -78,6 +162,7 @@ impl OpenRGBReadable for Zone {
             leds_max,
             leds_count,
             matrix,
+            segments,
         })
     }
 }
@@ -96,13 +181,15 @@ impl OpenRGBWritable for Zone {
                 size += 0_u16.size(protocol);
             }
             Some(matrix) => {
-                size += matrix.len.size(protocol);
-                size += matrix.height.size(protocol);
-                size += matrix.width.size(protocol);
-                size += 0_u32.size(protocol) * matrix.data.len();
+                size += 0_u16.size(protocol);
+                size += matrix.data_size();
             }
         };
 
+        if protocol >= 4 {
+            size += self.segments.size(protocol);
+        }
+
         size
     }
 
@@ -122,94 +209,288 @@ impl OpenRGBWritable for Zone {
                 stream.write_value::<u16>(0, protocol)?;
             }
             Some(matrix) => {
-                stream.write_value::<u16>(matrix.len, protocol)?;
-                stream.write_value::<u32>(matrix.height, protocol)?;
-                stream.write_value::<u32>(matrix.width, protocol)?;
+                let matrix_len: u16 = matrix.data_size().try_into().map_err(|_| {
+                    OpenRGBError::ProtocolError(format!(
+                        "matrix of {}x{} is too large",
+                        matrix.num_rows, matrix.num_columns
+                    ))
+                })?;
+                stream.write_value(matrix_len, protocol)?;
+                stream.write_value(matrix.num_rows, protocol)?;
+                stream.write_value(matrix.num_columns, protocol)?;
                 for data in matrix.data {
                     stream.write_value(data, protocol)?;
                 }
             }
         };
 
+        if protocol >= 4 {
+            stream.write_value(self.segments, protocol)?;
+        }
+
         Ok(())
     }
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use std::error::Error;
-
-//     use array2d::Array2D;
-//     use tokio_test::io::Builder;
-
-//     use crate::data::{Zone, ZoneType};
-//     use crate::protocol::OpenRGBReadableStream;
-//     use crate::tests::setup;
-//     use crate::DEFAULT_PROTOCOL;
-
-//     #[tokio::test]
-//     async fn test_read_001() -> Result<(), Box<dyn Error>> {
-//         setup()?;
-
-//         let mut stream = Builder::new()
-//             .read(&5_u16.to_le_bytes()) // name len
-//             .read(b"test\0") // name
-//             .read(&1_u32.to_le_bytes()) // type
-//             .read(&3_u32.to_le_bytes()) // leds_min
-//             .read(&18_u32.to_le_bytes()) // leds_max
-//             .read(&15_u32.to_le_bytes()) // leds_count
-//             .read(&0_u16.to_le_bytes()) // matrix_len
-//             .build();
-
-//         assert_eq!(
-//             stream.read_value::<Zone>(DEFAULT_PROTOCOL).await?,
-//             Zone {
-//                 name: "test".to_string(),
-//                 r#type: ZoneType::Linear,
-//                 leds_min: 3,
-//                 leds_max: 18,
-//                 leds_count: 15,
-//                 matrix: None,
-//             }
-//         );
-
-//         Ok(())
-//     }
-
-//     #[tokio::test]
-//     async fn test_read_002() -> Result<(), Box<dyn Error>> {
-//         setup()?;
-
-//         let mut stream = Builder::new()
-//             .read(&5_u16.to_le_bytes()) // name len
-//             .read(b"test\0") // name
-//             .read(&1_u32.to_le_bytes()) // type
-//             .read(&3_u32.to_le_bytes()) // leds_min
-//             .read(&18_u32.to_le_bytes()) // leds_max
-//             .read(&15_u32.to_le_bytes()) // leds_count
-//             .read(&32_u16.to_le_bytes()) // matrix_len
-//             .read(&2_u32.to_le_bytes()) // matrix_height
-//             .read(&3_u32.to_le_bytes()) // matrix_width
-//             .read(&0_u32.to_le_bytes()) // matrix[0]
-//             .read(&1_u32.to_le_bytes()) // matrix[1]
-//             .read(&2_u32.to_le_bytes()) // matrix[2]
-//             .read(&3_u32.to_le_bytes()) // matrix[3]
-//             .read(&4_u32.to_le_bytes()) // matrix[4]
-//             .read(&5_u32.to_le_bytes()) // matrix[5]
-//             .build();
-
-//         assert_eq!(
-//             stream.read_value::<Zone>(DEFAULT_PROTOCOL).await?,
-//             Zone {
-//                 name: "test".to_string(),
-//                 r#type: ZoneType::Linear,
-//                 leds_min: 3,
-//                 leds_max: 18,
-//                 leds_count: 15,
-//                 matrix: Some(Array2D::from_rows(&[vec![0, 1, 2], vec![3, 4, 5]])),
-//             }
-//         );
-
-//         Ok(())
-//     }
-// }
+#[cfg(test)]
+mod tests {
+    use crate::tests::Builder;
+    use crate::OpenRGBError;
+
+    use crate::protocol::{OpenRGBReadableSync, OpenRGBWritableSync};
+    use crate::DEFAULT_PROTOCOL;
+    use crate::{Matrix, Segment, Zone, ZoneType};
+
+    #[test]
+    fn test_read_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new()
+            .read(&5_u16.to_le_bytes()) // name len
+            .read(b"test\0") // name
+            .read(&1_u32.to_le_bytes()) // type
+            .read(&3_u32.to_le_bytes()) // leds_min
+            .read(&18_u32.to_le_bytes()) // leds_max
+            .read(&15_u32.to_le_bytes()) // leds_count
+            .read(&0_u16.to_le_bytes()) // matrix_len
+            .read(&0_u16.to_le_bytes()) // segments count
+            .build();
+
+        assert_eq!(
+            stream.read_value::<Zone>(DEFAULT_PROTOCOL)?,
+            Zone {
+                name: "test".to_string(),
+                r#type: ZoneType::Linear,
+                leds_min: 3,
+                leds_max: 18,
+                leds_count: 15,
+                matrix: None,
+                segments: vec![],
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_002() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new()
+            .read(&5_u16.to_le_bytes()) // name len
+            .read(b"test\0") // name
+            .read(&1_u32.to_le_bytes()) // type
+            .read(&3_u32.to_le_bytes()) // leds_min
+            .read(&18_u32.to_le_bytes()) // leds_max
+            .read(&15_u32.to_le_bytes()) // leds_count
+            .read(&32_u16.to_le_bytes()) // matrix_len
+            .read(&2_u32.to_le_bytes()) // matrix_height
+            .read(&3_u32.to_le_bytes()) // matrix_width
+            .read(&0_u32.to_le_bytes()) // matrix[0]
+            .read(&1_u32.to_le_bytes()) // matrix[1]
+            .read(&2_u32.to_le_bytes()) // matrix[2]
+            .read(&3_u32.to_le_bytes()) // matrix[3]
+            .read(&4_u32.to_le_bytes()) // matrix[4]
+            .read(&5_u32.to_le_bytes()) // matrix[5]
+            .read(&0_u16.to_le_bytes()) // segments count
+            .build();
+
+        assert_eq!(
+            stream.read_value::<Zone>(DEFAULT_PROTOCOL)?,
+            Zone {
+                name: "test".to_string(),
+                r#type: ZoneType::Linear,
+                leds_min: 3,
+                leds_max: 18,
+                leds_count: 15,
+                matrix: Matrix::from_row_major(vec![0, 1, 2, 3, 4, 5], 2, 3),
+                segments: vec![],
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_003() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new()
+            .read(&5_u16.to_le_bytes()) // name len
+            .read(b"test\0") // name
+            .read(&1_u32.to_le_bytes()) // type
+            .read(&0_u32.to_le_bytes()) // leds_min
+            .read(&40_u32.to_le_bytes()) // leds_max
+            .read(&24_u32.to_le_bytes()) // leds_count
+            .read(&0_u16.to_le_bytes()) // matrix_len
+            .read(&2_u16.to_le_bytes()) // segments count
+            .read(&6_u16.to_le_bytes()) // segment[0] name len
+            .read(b"Fan 1\0") // segment[0] name
+            .read(&1_u32.to_le_bytes()) // segment[0] type
+            .read(&0_u32.to_le_bytes()) // segment[0] start_idx
+            .read(&12_u32.to_le_bytes()) // segment[0] leds_count
+            .read(&6_u16.to_le_bytes()) // segment[1] name len
+            .read(b"Fan 2\0") // segment[1] name
+            .read(&1_u32.to_le_bytes()) // segment[1] type
+            .read(&12_u32.to_le_bytes()) // segment[1] start_idx
+            .read(&12_u32.to_le_bytes()) // segment[1] leds_count
+            .build();
+
+        assert_eq!(
+            stream.read_value::<Zone>(DEFAULT_PROTOCOL)?,
+            Zone {
+                name: "test".to_string(),
+                r#type: ZoneType::Linear,
+                leds_min: 0,
+                leds_max: 40,
+                leds_count: 24,
+                matrix: None,
+                segments: vec![
+                    Segment {
+                        name: "Fan 1".to_string(),
+                        r#type: ZoneType::Linear,
+                        start_idx: 0,
+                        leds_count: 12,
+                    },
+                    Segment {
+                        name: "Fan 2".to_string(),
+                        r#type: ZoneType::Linear,
+                        start_idx: 12,
+                        leds_count: 12,
+                    },
+                ],
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_protocol_3() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new()
+            .read(&5_u16.to_le_bytes()) // name len
+            .read(b"test\0") // name
+            .read(&1_u32.to_le_bytes()) // type
+            .read(&3_u32.to_le_bytes()) // leds_min
+            .read(&18_u32.to_le_bytes()) // leds_max
+            .read(&15_u32.to_le_bytes()) // leds_count
+            .read(&0_u16.to_le_bytes()) // matrix_len
+            .build();
+
+        assert_eq!(
+            stream.read_value::<Zone>(3)?,
+            Zone {
+                name: "test".to_string(),
+                r#type: ZoneType::Linear,
+                leds_min: 3,
+                leds_max: 18,
+                leds_count: 15,
+                matrix: None,
+                segments: vec![],
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_001() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new()
+            .write(&5_u16.to_le_bytes()) // name len
+            .write(b"test\0") // name
+            .write(&1_u32.to_le_bytes()) // type
+            .write(&3_u32.to_le_bytes()) // leds_min
+            .write(&18_u32.to_le_bytes()) // leds_max
+            .write(&15_u32.to_le_bytes()) // leds_count
+            .write(&32_u16.to_le_bytes()) // matrix_len
+            .write(&2_u32.to_le_bytes()) // matrix_height
+            .write(&3_u32.to_le_bytes()) // matrix_width
+            .write(&0_u32.to_le_bytes()) // matrix[0]
+            .write(&1_u32.to_le_bytes()) // matrix[1]
+            .write(&2_u32.to_le_bytes()) // matrix[2]
+            .write(&3_u32.to_le_bytes()) // matrix[3]
+            .write(&4_u32.to_le_bytes()) // matrix[4]
+            .write(&5_u32.to_le_bytes()) // matrix[5]
+            .write(&0_u16.to_le_bytes()) // segments count
+            .build();
+
+        stream.write_value(
+            Zone {
+                name: "test".to_string(),
+                r#type: ZoneType::Linear,
+                leds_min: 3,
+                leds_max: 18,
+                leds_count: 15,
+                matrix: Matrix::from_row_major(vec![0, 1, 2, 3, 4, 5], 2, 3),
+                segments: vec![],
+            },
+            DEFAULT_PROTOCOL,
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_matrix_overflow() -> Result<(), OpenRGBError> {
+        let mut stream = Builder::new()
+            .read(&5_u16.to_le_bytes()) // name len
+            .read(b"test\0") // name
+            .read(&1_u32.to_le_bytes()) // type
+            .read(&3_u32.to_le_bytes()) // leds_min
+            .read(&18_u32.to_le_bytes()) // leds_max
+            .read(&15_u32.to_le_bytes()) // leds_count
+            .read(&32_u16.to_le_bytes()) // matrix_len
+            .read(&u32::MAX.to_le_bytes()) // matrix_height
+            .read(&u32::MAX.to_le_bytes()) // matrix_width
+            .build();
+
+        assert!(matches!(
+            stream.read_value::<Zone>(DEFAULT_PROTOCOL),
+            Err(OpenRGBError::ProtocolError(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_resize() {
+        let mut zone = Zone {
+            name: "test".to_string(),
+            r#type: ZoneType::Linear,
+            leds_min: 3,
+            leds_max: 18,
+            leds_count: 15,
+            matrix: None,
+            segments: vec![],
+        };
+
+        assert!(zone.check_resize(3).is_ok());
+        assert!(zone.check_resize(18).is_ok());
+        assert!(matches!(
+            zone.check_resize(2),
+            Err(OpenRGBError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            zone.check_resize(19),
+            Err(OpenRGBError::InvalidArgument(_))
+        ));
+
+        zone.r#type = ZoneType::Single;
+        assert!(matches!(
+            zone.check_resize(15),
+            Err(OpenRGBError::InvalidArgument(_))
+        ));
+
+        zone.r#type = ZoneType::Matrix;
+        assert!(matches!(
+            zone.check_resize(15),
+            Err(OpenRGBError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_matrix() {
+        let matrix = Matrix::from_row_major(vec![0, 1, 2, 3, 4, Matrix::NO_LED], 2, 3).unwrap();
+
+        assert_eq!(matrix.num_rows(), 2);
+        assert_eq!(matrix.num_columns(), 3);
+        assert_eq!(matrix.get(1, 0), Some(3));
+        assert_eq!(matrix.get(1, 2), Some(Matrix::NO_LED));
+        assert_eq!(matrix.get(2, 0), None);
+        assert_eq!(Matrix::from_row_major(vec![0, 1], 2, 3), None);
+    }
+}
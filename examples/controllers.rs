@@ -9,20 +9,13 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     let client = OpenRGB::connect().await?;
 
-    // query controllers count
-    let controllers = client.get_controller_count().await?;
-    println!("{} controllers", controllers);
+    // query all controllers data
+    let controllers = client.get_all_controllers().await?;
+    println!("{} controllers", controllers.len());
 
-    // query and print each controller data
-    for controller_id in 0..controllers {
-        if controller_id == 15 {
-            println!("getting 16");
-        }
-        println!(
-            "controller {}: {:#?}",
-            controller_id,
-            client.get_controller(controller_id).await?
-        );
+    // print each controller data
+    for (controller_id, controller) in controllers.iter().enumerate() {
+        println!("controller {}: {:#?}", controller_id, controller);
     }
 
     Ok(())
@@ -0,0 +1,308 @@
+use std::env;
+use std::time::Duration;
+
+use tokio::net::TcpStream;
+
+use crate::client::{tcp_connect, ClientConfig, ProtocolSelection};
+use crate::data::DecodeLimits;
+use crate::protocol::OpenRGBStream;
+use crate::{OpenRGB, OpenRGBError, ReconnectPolicy, DEFAULT_ADDR, DEFAULT_PROTOCOL};
+
+/// Environment variable used by [OpenRGBBuilder] to get server host, if not set explicitly.
+pub static OPENRGB_HOST_ENV: &str = "OPENRGB_HOST";
+
+/// Environment variable used by [OpenRGBBuilder] to get server port, if not set explicitly.
+pub static OPENRGB_PORT_ENV: &str = "OPENRGB_PORT";
+
+/// Builder for [OpenRGB] client.
+///
+/// # Example
+///
+/// ```no_run
+/// # use openrgb::OpenRGBBuilder;
+/// # use std::error::Error;
+/// # use std::time::Duration;
+/// #
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn Error>> {
+/// let client = OpenRGBBuilder::new()
+///     .host("192.168.1.10")
+///     .name("my client")
+///     .request_timeout(Duration::from_secs(5))
+///     .connect()
+///     .await?;
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct OpenRGBBuilder {
+    host: Option<String>,
+    port: Option<u16>,
+    name: Option<String>,
+    protocol: Option<ProtocolSelection>,
+    connect_timeout: Option<Duration>,
+    request_timeout: Option<Duration>,
+    reconnect: Option<ReconnectPolicy>,
+    decode_limits: Option<DecodeLimits>,
+}
+
+impl OpenRGBBuilder {
+    /// Create a new builder with default settings.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set server host.
+    ///
+    /// Defaults to `OPENRGB_HOST` environment variable if set, otherwise to [DEFAULT_ADDR] host.
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.host = Some(host.into());
+        self
+    }
+
+    /// Set server port.
+    ///
+    /// Defaults to `OPENRGB_PORT` environment variable if set, otherwise to [DEFAULT_ADDR] port.
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// Set client name, sent to server as soon as connected.
+    ///
+    /// See [OpenRGB::set_name].
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Set maximum protocol version to negotiate with server.
+    ///
+    /// Defaults to [DEFAULT_PROTOCOL]. Versions above [DEFAULT_PROTOCOL] are not supported by this
+    /// client, and connecting with them fails with [OpenRGBError::InvalidArgument].
+    pub fn max_protocol(mut self, protocol: u32) -> Self {
+        self.protocol = Some(ProtocolSelection::Negotiate(protocol));
+        self
+    }
+
+    /// Use given protocol version without negotiating it with server.
+    ///
+    /// This is useful to talk to old servers, which do not support protocol version negotiation.
+    /// Versions above [DEFAULT_PROTOCOL] are not supported by this client, and connecting with them
+    /// fails with [OpenRGBError::InvalidArgument].
+    pub fn force_protocol(mut self, protocol: u32) -> Self {
+        self.protocol = Some(ProtocolSelection::Force(protocol));
+        self
+    }
+
+    /// Set timeout for opening connection to server.
+    ///
+    /// Defaults to no timeout.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set timeout for each request to server.
+    ///
+    /// Requests that time out fail with [OpenRGBError::Timeout]. Defaults to no timeout.
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Set limits applied when decoding data received from server.
+    ///
+    /// Packets exceeding these limits fail with [OpenRGBError::ProtocolError]. Defaults to
+    /// [DecodeLimits::default].
+    pub fn decode_limits(mut self, limits: DecodeLimits) -> Self {
+        self.decode_limits = Some(limits);
+        self
+    }
+
+    /// Reconnect automatically when connection is lost, according to given policy.
+    ///
+    /// See [OpenRGB::connect_to_with_reconnect]. Defaults to no reconnection.
+    pub fn reconnect(mut self, policy: ReconnectPolicy) -> Self {
+        self.reconnect = Some(policy);
+        self
+    }
+
+    /// Connect to OpenRGB server using TCP.
+    pub async fn connect(self) -> Result<OpenRGB<TcpStream>, OpenRGBError> {
+        let config = self.config()?;
+        let host = match self.host.clone() {
+            Some(host) => host,
+            None => env::var(OPENRGB_HOST_ENV).unwrap_or_else(|_| DEFAULT_ADDR.0.to_string()),
+        };
+        let port = match self.port {
+            Some(port) => port,
+            None => match env::var(OPENRGB_PORT_ENV) {
+                Ok(port) => port.parse().map_err(|e| OpenRGBError::ConnectionError {
+                    addr: format!("{}:{}", host, port),
+                    source: std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!("invalid {} environment variable: {}", OPENRGB_PORT_ENV, e),
+                    ),
+                })?,
+                Err(_) => DEFAULT_ADDR.1,
+            },
+        };
+        let connect_timeout = self.connect_timeout;
+        let connect = move || {
+            let host = host.clone();
+            async move { tcp_connect((host.as_str(), port), connect_timeout).await }
+        };
+
+        match self.reconnect {
+            Some(policy) => OpenRGB::with_config_and_reconnect(config, connect, policy).await,
+            None => OpenRGB::with_config(config, connect().await?).await,
+        }
+    }
+
+    /// Build client from given stream.
+    ///
+    /// This expects a connected, ready to use stream. Connection settings (host, port, connection
+    /// timeout and reconnection policy) are ignored.
+    pub async fn build<S: OpenRGBStream + 'static>(
+        self,
+        stream: S,
+    ) -> Result<OpenRGB<S>, OpenRGBError> {
+        OpenRGB::with_config(self.config()?, stream).await
+    }
+
+    fn config(&self) -> Result<ClientConfig, OpenRGBError> {
+        let protocol = self
+            .protocol
+            .unwrap_or(ProtocolSelection::Negotiate(DEFAULT_PROTOCOL));
+        let (ProtocolSelection::Negotiate(version) | ProtocolSelection::Force(version)) = protocol;
+        if version > DEFAULT_PROTOCOL {
+            return Err(OpenRGBError::InvalidArgument(format!(
+                "protocol version {} is not supported, maximum is {}",
+                version, DEFAULT_PROTOCOL
+            )));
+        }
+        Ok(ClientConfig {
+            protocol,
+            name: self.name.clone(),
+            request_timeout: self.request_timeout,
+            decode_limits: self.decode_limits.unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use tokio_test::io::Builder;
+
+    use crate::data::DecodeLimits;
+    use crate::tests::{setup, OpenRGBMockBuilder};
+    use crate::{OpenRGBBuilder, OpenRGBError, DEFAULT_PROTOCOL};
+
+    #[tokio::test]
+    async fn test_name() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let stream = Builder::new()
+            .negotiate_default_protocol()
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&50_u32.to_le_bytes()) // packet id
+            .write(&5_u32.to_le_bytes()) // data size
+            .write(b"test\0") // name
+            .build();
+
+        OpenRGBBuilder::new().name("test").build(stream).await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_max_protocol() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let stream = Builder::new()
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&40_u32.to_le_bytes()) // packet id
+            .write(&4_u32.to_le_bytes()) // data size
+            .write(&2_u32.to_le_bytes()) // protocol version
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&40_u32.to_le_bytes()) // packet id
+            .read(&4_u32.to_le_bytes()) // data size
+            .read(&3_u32.to_le_bytes()) // protocol version
+            .build();
+
+        let client = OpenRGBBuilder::new().max_protocol(2).build(stream).await?;
+
+        assert_eq!(client.get_protocol_version(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_force_protocol() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let stream = Builder::new().build();
+
+        let client = OpenRGBBuilder::new()
+            .force_protocol(1)
+            .build(stream)
+            .await?;
+
+        assert_eq!(client.get_protocol_version(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_protocol() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        for builder in [
+            OpenRGBBuilder::new().max_protocol(DEFAULT_PROTOCOL + 1),
+            OpenRGBBuilder::new().force_protocol(DEFAULT_PROTOCOL + 1),
+        ] {
+            assert!(matches!(
+                builder.build(Builder::new().build()).await,
+                Err(OpenRGBError::InvalidArgument(_))
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_decode_limits_negotiation() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let stream = Builder::new()
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&40_u32.to_le_bytes()) // packet id
+            .write(&4_u32.to_le_bytes()) // data size
+            .write(&DEFAULT_PROTOCOL.to_le_bytes()) // protocol version
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&40_u32.to_le_bytes()) // packet id
+            .read(&4_u32.to_le_bytes()) // data size
+            .build();
+
+        let result = OpenRGBBuilder::new()
+            .decode_limits(DecodeLimits {
+                max_packet_size: 2,
+                ..Default::default()
+            })
+            .build(stream)
+            .await;
+
+        assert!(matches!(result, Err(OpenRGBError::ProtocolError(_))));
+
+        Ok(())
+    }
+}
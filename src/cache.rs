@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+
+use crate::data::{Color, Controller, DecodeLimits, OpenRGBWritable};
+use crate::protocol::decode;
+use crate::OpenRGBError;
+
+/// Controllers data cached by client, see [OpenRGB::get_cached_controller](crate::OpenRGB::get_cached_controller).
+///
+/// Cached colors are updated by colors refreshes and by colors sent by client, so they reflect the
+/// latest known state of each controller.
+#[derive(Debug, Default)]
+pub(crate) struct ControllerCache {
+    controllers: HashMap<u32, Controller>,
+
+    /// Incremented on each invalidation, so that replies to requests sent before are not cached.
+    generation: u64,
+}
+
+impl ControllerCache {
+    /// Current generation, to pass to [ControllerCache::insert] once a reply is received.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn get(&self, controller_id: u32) -> Option<&Controller> {
+        self.controllers.get(&controller_id)
+    }
+
+    /// Cache controller data, unless cache was invalidated since given generation.
+    pub fn insert(&mut self, generation: u64, controller_id: u32, controller: Controller) {
+        if generation == self.generation {
+            self.controllers.insert(controller_id, controller);
+        }
+    }
+
+    /// Forget a controller, eg: after changing its modes or zones.
+    pub fn remove(&mut self, controller_id: u32) {
+        self.controllers.remove(&controller_id);
+    }
+
+    /// Forget all controllers, eg: after server device list changed.
+    pub fn invalidate(&mut self) {
+        self.controllers.clear();
+        self.generation += 1;
+    }
+
+    /// Update cached colors of a controller, starting at given LED index.
+    ///
+    /// Colors beyond controller LEDs are ignored, like the server does.
+    pub fn set_colors(&mut self, controller_id: u32, offset: usize, colors: &[Color]) {
+        if let Some(controller) = self.controllers.get_mut(&controller_id) {
+            for (cached, color) in controller.colors.iter_mut().skip(offset).zip(colors) {
+                *cached = *color;
+            }
+        }
+    }
+
+    /// Update cached colors of a controller zone.
+    pub fn set_zone_colors(&mut self, controller_id: u32, zone_id: u32, colors: &[Color]) {
+        let zone = self.controllers.get(&controller_id).and_then(|controller| {
+            let zones = controller.zones.get(..=zone_id as usize)?;
+            let offset = zones
+                .iter()
+                .rev()
+                .skip(1)
+                .map(|zone| zone.leds_count)
+                .sum::<u32>();
+            Some((offset as usize, zones[zone_id as usize].leds_count as usize))
+        });
+        if let Some((offset, len)) = zone {
+            self.set_colors(controller_id, offset, &colors[..colors.len().min(len)]);
+        }
+    }
+}
+
+/// Decode only colors from a controller data reply, given the data size and number of LEDs of
+/// cached controller.
+///
+/// Colors are the last field of controller data, so they are decoded from the end of payload
+/// without decoding (and allocating) names, modes, zones and LEDs. Returns `None` if payload does
+/// not have the same declared data size or does not end with as many colors, eg: if controller
+/// changed, in which case it must be fully decoded.
+pub(crate) fn decode_colors(
+    payload: &[u8],
+    protocol: u32,
+    data_size: usize,
+    num_leds: usize,
+    limits: &DecodeLimits,
+) -> Result<Option<Vec<Color>>, OpenRGBError> {
+    if payload.len() != data_size
+        || payload.len() < 0_u32.size(protocol)
+        || decode::<u32>(payload, protocol, limits)? as usize != data_size
+    {
+        return Ok(None);
+    }
+    let count_size = 0_u16.size(protocol);
+    let len = count_size + num_leds * Color::default().size(protocol);
+    let start = match payload.len().checked_sub(len) {
+        Some(start) => start,
+        None => return Ok(None),
+    };
+    let colors = &payload[start..];
+    if decode::<u16>(&colors[..count_size], protocol, limits)? as usize != num_leds {
+        return Ok(None);
+    }
+    decode(colors, protocol, limits).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use crate::cache::{decode_colors, ControllerCache};
+    use crate::data::{
+        Color, Controller, DecodeLimits, DeviceType, OpenRGBWritable, Zone, ZoneType, LED,
+    };
+    use crate::protocol::HEADER_SIZE;
+    use crate::tests::controller_reply;
+    use crate::DEFAULT_PROTOCOL;
+
+    fn controller(colors: Vec<Color>) -> Controller {
+        let zone = |name: &str, leds_count| Zone {
+            name: name.into(),
+            r#type: ZoneType::Linear,
+            leds_min: 0,
+            leds_max: 10,
+            leds_count,
+            matrix: None,
+            segments: vec![],
+        };
+        Controller {
+            r#type: DeviceType::Cooler,
+            name: "Test Cooler".into(),
+            vendor: "".into(),
+            description: "".into(),
+            version: "".into(),
+            serial: "".into(),
+            location: "".into(),
+            active_mode: 0,
+            modes: vec![],
+            zones: vec![zone("Channel 1", 1), zone("Channel 2", 2)],
+            leds: (0..colors.len() as u32)
+                .map(|value| LED {
+                    name: format!("LED {}", value),
+                    value,
+                })
+                .collect(),
+            colors,
+        }
+    }
+
+    #[test]
+    fn test_decode_colors() -> Result<(), Box<dyn Error>> {
+        let colors = vec![
+            Color::new(1, 2, 3),
+            Color::new(4, 5, 6),
+            Color::new(7, 8, 9),
+        ];
+        let reply = controller_reply(0, controller(colors.clone()));
+        let payload = &reply[HEADER_SIZE..];
+        let size = payload.len();
+        let limits = DecodeLimits::default();
+
+        assert_eq!(
+            decode_colors(payload, DEFAULT_PROTOCOL, size, 3, &limits)?,
+            Some(colors)
+        );
+        assert_eq!(
+            decode_colors(payload, DEFAULT_PROTOCOL, size, 2, &limits)?,
+            None
+        );
+        assert_eq!(
+            decode_colors(&[], DEFAULT_PROTOCOL, size, 2, &limits)?,
+            None
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_colors_other_controller() -> Result<(), Box<dyn Error>> {
+        let cached = controller(vec![Color::default(); 3]);
+        let size = cached.size(DEFAULT_PROTOCOL);
+        let limits = DecodeLimits::default();
+
+        let mut other = controller(vec![Color::new(1, 2, 3); 3]);
+        other.name = "Other Cooler".into();
+        other.leds[0].name = "LED".into();
+        let reply = controller_reply(0, other);
+
+        assert_eq!(
+            decode_colors(&reply[HEADER_SIZE..], DEFAULT_PROTOCOL, size, 3, &limits)?,
+            None
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_colors() {
+        let mut cache = ControllerCache::default();
+        cache.insert(0, 2, controller(vec![Color::default(); 3]));
+
+        cache.set_colors(2, 0, &[Color::new(1, 1, 1); 4]);
+        assert_eq!(cache.get(2).unwrap().colors, [Color::new(1, 1, 1); 3]);
+
+        cache.set_zone_colors(2, 1, &[Color::new(2, 2, 2); 3]);
+        assert_eq!(
+            cache.get(2).unwrap().colors,
+            [
+                Color::new(1, 1, 1),
+                Color::new(2, 2, 2),
+                Color::new(2, 2, 2)
+            ]
+        );
+
+        cache.set_colors(2, 1, &[Color::new(3, 3, 3)]);
+        assert_eq!(cache.get(2).unwrap().colors[1], Color::new(3, 3, 3));
+    }
+
+    #[test]
+    fn test_invalidate() {
+        let mut cache = ControllerCache::default();
+        let generation = cache.generation();
+        cache.invalidate();
+
+        cache.insert(generation, 2, controller(vec![]));
+        assert!(cache.get(2).is_none());
+
+        cache.insert(cache.generation(), 2, controller(vec![]));
+        assert!(cache.get(2).is_some());
+    }
+}
@@ -1,34 +1,139 @@
+use std::collections::{HashMap, VecDeque};
 use std::fmt::Debug;
+use std::future::Future;
+use std::io::ErrorKind;
 use std::net::Ipv4Addr;
+#[cfg(unix)]
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
-use log::debug;
+use bytes::Bytes;
+use log::{debug, warn};
+use tokio::io::{split, AsyncWriteExt, ReadHalf, WriteHalf};
+#[cfg(unix)]
+use tokio::net::UnixStream;
 use tokio::net::{TcpStream, ToSocketAddrs};
-use tokio::sync::Mutex;
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+use tokio_util::codec::FramedRead;
 
 use OpenRGBError::*;
 use PacketId::*;
 
-use crate::data::{Color, Controller, Mode, OpenRGBWritable, PacketId, RawString};
-use crate::protocol::OpenRGBStream;
-use crate::OpenRGBError;
+use crate::cache::{decode_colors, ControllerCache};
+use crate::data::{
+    Color, Controller, DecodeLimits, DeviceKey, DeviceResolver, Mode, OpenRGBReadable,
+    OpenRGBWritable, PacketId, Plugin, RawBytes, RawString, Resolution, Segment,
+};
+use crate::protocol::{decode, encode_packet, OpenRGBStream};
+use crate::reconnect::{Reconnect, ReconnectPolicy};
+use crate::{ControllerHandle, OpenRGBCodec, OpenRGBError, OpenRGBEvent};
 
-/// Default protocol version used by [OpenRGB] client.
-pub static DEFAULT_PROTOCOL: u32 = 3;
+pub use openrgb_data::DEFAULT_PROTOCOL;
 
-/// Default address used by [OpenRGB::connect].
+/// Default address used by [OpenRGB::connect], and by [OpenRGBBuilder](crate::OpenRGBBuilder) if
+/// not overridden by environment.
 pub static DEFAULT_ADDR: (Ipv4Addr, u16) = (Ipv4Addr::LOCALHOST, 6742);
 
+/// Number of events buffered for each [OpenRGB::events] subscriber.
+static EVENTS_CAPACITY: usize = 16;
+
+/// Number of packets queued for writing before requests wait.
+static WRITE_QUEUE_CAPACITY: usize = 16;
+
 /// OpenRGB client.
+///
+/// Server packets are read by a background task, which routes replies to pending requests by
+/// device ID and packet ID, and publishes server notifications as [OpenRGBEvent]s (see [OpenRGB::events]).
+/// Concurrent requests are therefore pipelined over the connection, rather than waiting for each
+/// other's replies.
+///
+/// Packets are fully encoded before being queued for writing by another background task, so a
+/// request that is cancelled (eg: dropped after a timeout) never leaves a partially written
+/// packet on the connection, and its reply is discarded when it arrives. As a consequence,
+/// methods that expect no reply return as soon as their packet is queued.
 pub struct OpenRGB<S: OpenRGBStream> {
+    shared: Arc<Shared<S>>,
+    reader: JoinHandle<()>,
+}
+
+/// State shared between client and reader task.
+struct Shared<S: OpenRGBStream> {
+    protocol: AtomicU32,
+    pending: std::sync::Mutex<Pending>,
+    buffers: Buffers,
+    cache: std::sync::Mutex<ControllerCache>,
+    events: broadcast::Sender<OpenRGBEvent>,
+    name: std::sync::Mutex<Option<String>>,
+    reconnect: Option<Reconnect<S>>,
+    protocol_selection: ProtocolSelection,
+    request_timeout: Option<Duration>,
+    decode_limits: DecodeLimits,
+}
+
+/// How client chooses protocol version.
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum ProtocolSelection {
+    /// Negotiate with server, up to given version.
+    Negotiate(u32),
+
+    /// Use given version, without negotiating.
+    Force(u32),
+}
+
+/// Client settings, see [OpenRGBBuilder](crate::OpenRGBBuilder).
+#[derive(Debug, Clone)]
+pub(crate) struct ClientConfig {
+    pub protocol: ProtocolSelection,
+    pub name: Option<String>,
+    pub request_timeout: Option<Duration>,
+    pub decode_limits: DecodeLimits,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            protocol: ProtocolSelection::Negotiate(DEFAULT_PROTOCOL),
+            name: None,
+            request_timeout: None,
+            decode_limits: DecodeLimits::default(),
+        }
+    }
+}
+
+/// Packet buffers given back by writer task once written, reused to encode next packets.
+type Buffers = Arc<std::sync::Mutex<Vec<Vec<u8>>>>;
+
+/// Reply payload sent by reader task to a request.
+type Reply = Result<Bytes, OpenRGBError>;
+
+/// Reply expected by a request, once queued for writing.
+struct PendingResponse {
     protocol: u32,
-    pub stream: Arc<Mutex<S>>,
+    reply: oneshot::Receiver<Reply>,
+}
+
+/// Connection state shared between requests and reader task.
+#[derive(Default)]
+struct Pending {
+    /// Replies expected from server by device ID and packet ID, in request order.
+    replies: HashMap<(u32, PacketId), VecDeque<oneshot::Sender<Reply>>>,
+
+    /// Queue of encoded packets to write, or `None` while disconnected.
+    writer: Option<mpsc::Sender<Vec<u8>>>,
 }
 
 impl OpenRGB<TcpStream> {
-    /// Connect to default OpenRGB server.
+    /// Connect to default OpenRGB server, at [DEFAULT_ADDR].
     ///
-    /// Use [OpenRGB::connect_to] to connect to a specific server.
+    /// Use [OpenRGB::connect_to] to connect to a specific server, or
+    /// [OpenRGBBuilder](crate::OpenRGBBuilder) for more options (such as reading server address from
+    /// environment).
     ///
     /// # Example
     ///
@@ -67,109 +172,592 @@ impl OpenRGB<TcpStream> {
     /// # }
     /// ```
     pub async fn connect_to(addr: impl ToSocketAddrs + Debug + Copy) -> Result<Self, OpenRGBError> {
-        println!("Connecting to OpenRGB server at {:?}...", addr);
-        Self::new(
-            TcpStream::connect(addr)
-                .await
-                .map_err(|source| ConnectionError {
-                    addr: format!("{:?}", addr),
-                    source,
-                })?,
-        )
-        .await
+        Self::new(tcp_connect(addr, None).await?).await
+    }
+
+    /// Connect to OpenRGB server at given coordinates, and reconnect automatically when connection is lost.
+    ///
+    /// After connection is lost, the client tries to connect again according to given `policy`. Once
+    /// connected, it negotiates the protocol version again and restores the client name set with
+    /// [OpenRGB::set_name]. Requests made while disconnected fail with [OpenRGBError::CommunicationError].
+    ///
+    /// Subscribe to [OpenRGB::events] to be notified of connection loss and recovery.
+    ///
+    /// # Arguments
+    /// * `addr` - A socket address (eg: a `(host, port)` tuple)
+    /// * `policy` - Reconnection policy
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use openrgb::{OpenRGB, ReconnectPolicy};
+    /// # use std::error::Error;
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn Error>> {
+    /// let client = OpenRGB::connect_to_with_reconnect(("localhost", 6742), ReconnectPolicy::default()).await?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn connect_to_with_reconnect(
+        addr: impl ToSocketAddrs + Debug + Copy + Send + Sync + 'static,
+        policy: ReconnectPolicy,
+    ) -> Result<Self, OpenRGBError> {
+        Self::with_reconnect(move || tcp_connect(addr, None), policy).await
+    }
+}
+
+#[cfg(unix)]
+impl OpenRGB<UnixStream> {
+    /// Connect to OpenRGB server listening on given Unix domain socket.
+    ///
+    /// # Arguments
+    /// * `path` - Socket path
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use openrgb::OpenRGB;
+    /// # use std::error::Error;
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn Error>> {
+    /// let client = OpenRGB::connect_unix("/run/openrgb.sock").await?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn connect_unix(path: impl AsRef<Path>) -> Result<Self, OpenRGBError> {
+        let path = path.as_ref();
+        debug!("Connecting to OpenRGB server at {:?}...", path);
+        let stream = UnixStream::connect(path)
+            .await
+            .map_err(|source| ConnectionError {
+                addr: format!("{:?}", path),
+                source,
+            })?;
+        Self::new(stream).await
     }
 }
 
-impl<S: OpenRGBStream> OpenRGB<S> {
+/// Open TCP connection to server, failing after `timeout` if any.
+pub(crate) async fn tcp_connect(
+    addr: impl ToSocketAddrs + Debug + Copy,
+    timeout: Option<Duration>,
+) -> Result<TcpStream, OpenRGBError> {
+    debug!("Connecting to OpenRGB server at {:?}...", addr);
+    let result = match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, TcpStream::connect(addr))
+            .await
+            .unwrap_or_else(|_| {
+                Err(std::io::Error::new(
+                    ErrorKind::TimedOut,
+                    format!("connection timed out after {:?}", timeout),
+                ))
+            }),
+        None => TcpStream::connect(addr).await,
+    };
+    result.map_err(|source| ConnectionError {
+        addr: format!("{:?}", addr),
+        source,
+    })
+}
+
+impl<S: OpenRGBStream + 'static> OpenRGB<S> {
     /// Build a new client from given stream.
     ///
     /// This constructor expects a connected, ready to use stream.
-    pub async fn new(mut stream: S) -> Result<Self, OpenRGBError> {
-        let protocol = DEFAULT_PROTOCOL.min(
-            stream
-                .request(
-                    DEFAULT_PROTOCOL,
-                    0,
-                    RequestProtocolVersion,
-                    DEFAULT_PROTOCOL,
-                )
-                .await?,
-        );
+    ///
+    /// Use [OpenRGBBuilder::build](crate::OpenRGBBuilder::build) to customize client settings.
+    pub async fn new(stream: S) -> Result<Self, OpenRGBError> {
+        Self::with_config(Default::default(), stream).await
+    }
 
-        debug!(
-            "Connected to OpenRGB server using protocol version {:?}",
-            protocol
-        );
+    /// Build a new client from given connection function, and reconnect automatically when connection is lost.
+    ///
+    /// `connect` is called to open the initial connection, then each time the client tries to reconnect
+    /// according to given `policy`. See [OpenRGB::connect_to_with_reconnect] for details.
+    pub async fn with_reconnect<F, Fut>(
+        connect: F,
+        policy: ReconnectPolicy,
+    ) -> Result<Self, OpenRGBError>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<S, OpenRGBError>> + Send + 'static,
+    {
+        Self::with_config_and_reconnect(Default::default(), connect, policy).await
+    }
+
+    pub(crate) async fn with_config(config: ClientConfig, stream: S) -> Result<Self, OpenRGBError> {
+        Self::build(config, stream, None).await
+    }
+
+    pub(crate) async fn with_config_and_reconnect<F, Fut>(
+        config: ClientConfig,
+        connect: F,
+        policy: ReconnectPolicy,
+    ) -> Result<Self, OpenRGBError>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<S, OpenRGBError>> + Send + 'static,
+    {
+        let stream = connect().await?;
+        Self::build(
+            config,
+            stream,
+            Some(Reconnect {
+                connect: Box::new(move || Box::pin(connect())),
+                policy,
+            }),
+        )
+        .await
+    }
+
+    async fn build(
+        config: ClientConfig,
+        mut stream: S,
+        reconnect: Option<Reconnect<S>>,
+    ) -> Result<Self, OpenRGBError> {
+        let protocol = with_timeout(
+            config.request_timeout,
+            open_session(
+                &mut stream,
+                config.protocol,
+                config.name.clone(),
+                &config.decode_limits,
+            ),
+        )
+        .await?;
+
+        let (reader, writer) = split(stream);
+        let (events, _) = broadcast::channel(EVENTS_CAPACITY);
+        let buffers = Buffers::default();
+        let shared = Arc::new(Shared {
+            protocol: AtomicU32::new(protocol),
+            pending: std::sync::Mutex::new(Pending {
+                replies: HashMap::new(),
+                writer: Some(spawn_writer(writer, buffers.clone())),
+            }),
+            buffers,
+            cache: Default::default(),
+            events,
+            name: std::sync::Mutex::new(config.name),
+            reconnect,
+            protocol_selection: config.protocol,
+            request_timeout: config.request_timeout,
+            decode_limits: config.decode_limits,
+        });
 
         Ok(Self {
-            protocol,
-            stream: Arc::new(Mutex::new(stream)),
+            shared: shared.clone(),
+            reader: tokio::spawn(read_loop(reader, shared)),
+        })
+    }
+
+    /// Subscribe to events published by server.
+    ///
+    /// Only events published after subscription are received. Subscribers that do not keep up
+    /// miss the oldest events (see [broadcast::Receiver::recv]).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use openrgb::{OpenRGB, OpenRGBEvent};
+    /// # use std::error::Error;
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn Error>> {
+    /// let client = OpenRGB::connect().await?;
+    /// let mut events = client.events();
+    /// while let Ok(event) = events.recv().await {
+    ///     if event == OpenRGBEvent::DeviceListUpdated {
+    ///         println!("{} controllers", client.get_controller_count().await?);
+    ///     }
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn events(&self) -> broadcast::Receiver<OpenRGBEvent> {
+        self.shared.events.subscribe()
+    }
+
+    /// Subscribe to events published by server, as a [Stream].
+    ///
+    /// This is the same as [OpenRGB::events], except that events missed by a subscriber that
+    /// does not keep up are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use openrgb::OpenRGB;
+    /// # use std::error::Error;
+    /// use tokio_stream::StreamExt;
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn Error>> {
+    /// let client = OpenRGB::connect().await?;
+    /// let mut events = client.event_stream();
+    /// while let Some(event) = events.next().await {
+    ///     println!("{:?}", event);
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn event_stream(&self) -> impl Stream<Item = OpenRGBEvent> {
+        BroadcastStream::new(self.shared.events.subscribe()).filter_map(|event| match event {
+            Ok(event) => Some(event),
+            Err(error) => {
+                warn!("Skipping OpenRGB events: {}", error);
+                None
+            }
         })
     }
 
     /// Get protocol version negotiated with server.
     ///
-    /// This is the lowest between this client maximum supported version ([DEFAULT_PROTOCOL]) and server version.
+    /// This is the lowest between this client maximum supported version ([DEFAULT_PROTOCOL]) and server version,
+    /// unless capped or forced with [OpenRGBBuilder](crate::OpenRGBBuilder).
     ///
     /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#protocol-versions) for more information.
     pub fn get_protocol_version(&self) -> u32 {
-        self.protocol
+        self.shared.protocol.load(Ordering::Relaxed)
     }
 
     /// Set client name.
     ///
     /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_set_client_name) for more information.
     pub async fn set_name(&self, name: impl Into<String>) -> Result<(), OpenRGBError> {
-        self.stream
-            .lock()
-            .await
-            .write_packet(self.protocol, 0, SetClientName, RawString(name.into()))
-            .await
+        let name = name.into();
+        *self.shared.name.lock().unwrap() = Some(name.clone());
+        self.write_packet(0, SetClientName, RawString(name)).await
     }
 
     /// Get number of controllers.
     ///
     /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_request_controller_count) for more information.
     pub async fn get_controller_count(&self) -> Result<u32, OpenRGBError> {
-        self.stream
-            .lock()
-            .await
-            .request(self.protocol, 0, RequestControllerCount, ())
-            .await
+        self.request(0, RequestControllerCount, ()).await
     }
 
     /// Get controller data.
     ///
+    /// Controller data is also cached, see [OpenRGB::get_cached_controller].
+    ///
     /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_request_controller_data) for more information.
     pub async fn get_controller(&self, controller_id: u32) -> Result<Controller, OpenRGBError> {
-        self.stream
-            .lock()
-            .await
+        let generation = self.shared.cache.lock().unwrap().generation();
+        let controller = self
             .request(
-                self.protocol,
                 controller_id,
                 RequestControllerData,
-                self.protocol,
+                self.get_protocol_version(),
             )
-            .await
+            .await?;
+        self.cache_controller(generation, controller_id, &controller);
+        Ok(controller)
     }
 
-    /// Resize a controller zone.
+    /// Get controller data from client cache, fetching it only if not cached yet.
     ///
-    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_rgbcontroller_resizezone) for more information.
-    pub async fn resize_zone(&self, zone_id: i32, new_size: i32) -> Result<(), OpenRGBError> {
-        self.stream
+    /// Cache is filled by [OpenRGB::get_controller] and [OpenRGB::get_controllers], and cleared when
+    /// server device list changes (see [OpenRGBEvent::DeviceListUpdated]) or connection is lost.
+    /// Cached colors are kept up to date with colors sent by this client (eg: [OpenRGB::update_leds]),
+    /// but not with colors set by other clients, see [OpenRGB::refresh_colors] for that.
+    pub async fn get_cached_controller(
+        &self,
+        controller_id: u32,
+    ) -> Result<Controller, OpenRGBError> {
+        let cached = self
+            .shared
+            .cache
             .lock()
-            .await
-            .write_packet(
-                self.protocol,
-                0,
-                RGBControllerResizeZone,
-                (zone_id, new_size),
+            .unwrap()
+            .get(controller_id)
+            .cloned();
+        match cached {
+            Some(controller) => Ok(controller),
+            None => self.get_controller(controller_id).await,
+        }
+    }
+
+    /// Get cached controller colors, without any round trip to server.
+    ///
+    /// Returns `None` if controller is not cached, see [OpenRGB::get_cached_controller].
+    pub fn get_cached_colors(&self, controller_id: u32) -> Option<Vec<Color>> {
+        let cache = self.shared.cache.lock().unwrap();
+        cache
+            .get(controller_id)
+            .map(|controller| controller.colors.clone())
+    }
+
+    /// Fetch current controller colors, and update cached controller with them.
+    ///
+    /// The protocol has no colors only request, so server still sends the whole controller data,
+    /// but only its colors are decoded when controller is cached, which saves decoding and
+    /// allocating names, modes, zones and LEDs. Controller is fully fetched if not cached yet,
+    /// or if its data size or number of LEDs changed.
+    ///
+    /// Cached modes, zones and other metadata are not refreshed: changes made by other clients
+    /// that keep the same data size and number of LEDs (eg: switching active mode) go unnoticed
+    /// until the cache is invalidated, see [OpenRGB::invalidate_cache].
+    pub async fn refresh_colors(&self, controller_id: u32) -> Result<Vec<Color>, OpenRGBError> {
+        let generation = {
+            let cache = self.shared.cache.lock().unwrap();
+            cache.get(controller_id).map(|_| cache.generation())
+        };
+        let generation = match generation {
+            Some(generation) => generation,
+            None => return Ok(self.get_controller(controller_id).await?.colors),
+        };
+
+        let response = self
+            .queue_request(
+                controller_id,
+                RequestControllerData,
+                self.get_protocol_version(),
             )
+            .await?;
+        let protocol = response.protocol;
+        let payload = self.receive_payload(response).await?;
+        let limits = &self.shared.decode_limits;
+
+        let cached = {
+            let cache = self.shared.cache.lock().unwrap();
+            cache
+                .get(controller_id)
+                .filter(|_| cache.generation() == generation)
+                .map(|controller| (controller.size(protocol), controller.colors.len()))
+        };
+        let colors = match cached {
+            Some((data_size, num_leds)) => {
+                decode_colors(&payload, protocol, data_size, num_leds, limits)?
+            }
+            None => None,
+        };
+
+        match colors {
+            Some(colors) => {
+                let mut cache = self.shared.cache.lock().unwrap();
+                if cache.generation() == generation {
+                    cache.set_colors(controller_id, 0, &colors);
+                }
+                Ok(colors)
+            }
+            None => {
+                let controller: Controller = decode(&payload, protocol, limits)?;
+                self.cache_controller(generation, controller_id, &controller);
+                Ok(controller.colors)
+            }
+        }
+    }
+
+    /// Clear controllers cache, see [OpenRGB::get_cached_controller].
+    pub fn invalidate_cache(&self) {
+        self.shared.cache.lock().unwrap().invalidate();
+    }
+
+    /// Get data of several controllers.
+    ///
+    /// Requests are pipelined: all of them are sent before waiting for replies, which saves a round trip
+    /// per controller compared to calling [OpenRGB::get_controller] in a loop.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use openrgb::OpenRGB;
+    /// # use std::error::Error;
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn Error>> {
+    /// let client = OpenRGB::connect().await?;
+    /// let count = client.get_controller_count().await?;
+    /// let controllers = client.get_controllers(0..count).await?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_controllers(
+        &self,
+        controller_ids: impl IntoIterator<Item = u32>,
+    ) -> Result<Vec<Controller>, OpenRGBError> {
+        let protocol = self.get_protocol_version();
+        let generation = self.shared.cache.lock().unwrap().generation();
+        let mut responses = Vec::new();
+        for controller_id in controller_ids {
+            responses.push((
+                controller_id,
+                self.queue_request(controller_id, RequestControllerData, protocol)
+                    .await?,
+            ));
+        }
+
+        let mut controllers = Vec::with_capacity(responses.len());
+        for (controller_id, response) in responses {
+            let controller = self.receive(response).await?;
+            self.cache_controller(generation, controller_id, &controller);
+            controllers.push(controller);
+        }
+        Ok(controllers)
+    }
+
+    /// Get data of all controllers.
+    ///
+    /// Enumeration restarts if server device list changes meanwhile (ie: a
+    /// [OpenRGBEvent::DeviceListUpdated] event is received, or controller count changes), so that the
+    /// returned controllers are a consistent snapshot of server devices.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use openrgb::OpenRGB;
+    /// # use std::error::Error;
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn Error>> {
+    /// let client = OpenRGB::connect().await?;
+    /// for controller in client.get_all_controllers().await? {
+    ///     println!("{}", controller.name);
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_all_controllers(&self) -> Result<Vec<Controller>, OpenRGBError> {
+        loop {
+            let mut events = self.events();
+            let count = self.get_controller_count().await?;
+
+            // server never replies for removed controllers, so do not wait for them
+            let controllers = tokio::select! {
+                biased;
+                controllers = self.get_controllers(0..count) => controllers?,
+                _ = next_device_list_update(&mut events) => {
+                    debug!("OpenRGB server device list changed during enumeration, restarting");
+                    self.forget_cancelled_requests(RequestControllerData);
+                    continue;
+                }
+            };
+
+            if self.get_controller_count().await? == count && !device_list_updated(&mut events) {
+                return Ok(controllers);
+            }
+            debug!("OpenRGB server device list changed during enumeration, restarting");
+        }
+    }
+
+    /// Get a handle on a controller, to update its zones and LEDs by name.
+    ///
+    /// See [ControllerHandle].
+    pub async fn controller(
+        &self,
+        controller_id: u32,
+    ) -> Result<ControllerHandle<'_, S>, OpenRGBError> {
+        let controller = self.get_controller(controller_id).await?;
+        Ok(ControllerHandle::new(self, controller_id, controller))
+    }
+
+    /// Get a handle on the controller of given device, see [DeviceKey] and [ControllerHandle].
+    ///
+    /// Fails with [OpenRGBError::InvalidArgument] if device is missing or ambiguous.
+    pub async fn controller_by_key(
+        &self,
+        key: &DeviceKey,
+    ) -> Result<ControllerHandle<'_, S>, OpenRGBError> {
+        let mut controllers = self.get_all_controllers().await?;
+        match DeviceResolver::new(&controllers).resolve(key) {
+            Resolution::Found(controller_id) => {
+                let controller = controllers.swap_remove(controller_id as usize);
+                Ok(ControllerHandle::new(self, controller_id, controller))
+            }
+            Resolution::Missing => Err(InvalidArgument(format!("device {:?} not found", key))),
+            Resolution::Ambiguous(ids) => Err(InvalidArgument(format!(
+                "device {:?} matches controllers {:?}",
+                key, ids
+            ))),
+        }
+    }
+
+    /// Find current controller IDs of given devices, see [DeviceResolver].
+    pub async fn resolve_devices(
+        &self,
+        keys: impl IntoIterator<Item = &DeviceKey>,
+    ) -> Result<Vec<Resolution>, OpenRGBError> {
+        let controllers = self.get_all_controllers().await?;
+        Ok(DeviceResolver::new(&controllers).resolve_all(keys))
+    }
+
+    /// Get handles on all controllers, see [OpenRGB::get_all_controllers] and [ControllerHandle].
+    pub async fn controllers(&self) -> Result<Vec<ControllerHandle<'_, S>>, OpenRGBError> {
+        Ok(self
+            .get_all_controllers()
+            .await?
+            .into_iter()
+            .enumerate()
+            .map(|(id, controller)| ControllerHandle::new(self, id as u32, controller))
+            .collect())
+    }
+
+    /// Resize a controller zone, and return refreshed controller data.
+    ///
+    /// Zone type and bounds are checked first, see [Zone::check_resize](crate::data::Zone::check_resize).
+    /// LED indices change after a resize, so previously fetched controller data is outdated.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_rgbcontroller_resizezone) for more information.
+    pub async fn resize_zone(
+        &self,
+        controller_id: u32,
+        zone_id: u32,
+        new_size: u32,
+    ) -> Result<Controller, OpenRGBError> {
+        let controller = self.get_controller(controller_id).await?;
+        controller
+            .zones
+            .get(zone_id as usize)
+            .ok_or_else(|| InvalidArgument(format!("unknown zone ID {}", zone_id)))?
+            .check_resize(new_size)?;
+        self.write_packet(controller_id, RGBControllerResizeZone, (zone_id, new_size))
+            .await?;
+        self.shared.cache.lock().unwrap().remove(controller_id);
+        self.get_controller(controller_id).await
+    }
+
+    /// Remove all segments of a controller zone.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_rgbcontroller_clearsegments) for more information.
+    pub async fn clear_segments(
+        &self,
+        controller_id: u32,
+        zone_id: u32,
+    ) -> Result<(), OpenRGBError> {
+        self.check_protocol_version_segments()?;
+        self.write_packet(controller_id, RGBControllerClearSegments, zone_id)
             .await
     }
 
+    /// Add a segment to a controller zone.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_rgbcontroller_addsegment) for more information.
+    pub async fn add_segment(
+        &self,
+        controller_id: u32,
+        zone_id: u32,
+        segment: Segment,
+    ) -> Result<(), OpenRGBError> {
+        self.check_protocol_version_segments()?;
+        self.write_packet(
+            controller_id,
+            RGBControllerAddSegment,
+            (
+                zone_id.size(self.get_protocol_version())
+                    + segment.size(self.get_protocol_version()),
+                zone_id,
+                segment,
+            ),
+        )
+        .await
+    }
+
     /// Update a single LED.
     ///
     /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_rgbcontroller_updatesingleled) for more information.
@@ -179,61 +767,64 @@ impl<S: OpenRGBStream> OpenRGB<S> {
         led_id: i32,
         color: Color,
     ) -> Result<(), OpenRGBError> {
-        self.stream
-            .lock()
-            .await
-            .write_packet(
-                self.protocol,
-                controller_id,
-                RGBControllerUpdateSingleLed,
-                (led_id, color),
-            )
-            .await
+        self.write_packet(controller_id, RGBControllerUpdateSingleLed, (led_id, color))
+            .await?;
+        if let Ok(led_id) = usize::try_from(led_id) {
+            let mut cache = self.shared.cache.lock().unwrap();
+            cache.set_colors(controller_id, led_id, &[color]);
+        }
+        Ok(())
     }
 
     /// Update LEDs.
     ///
+    /// Colors are borrowed, and encoded into a buffer reused across calls, so that streaming
+    /// colors at a high frame rate does not allocate.
+    ///
     /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_rgbcontroller_updateleds) for more information.
     pub async fn update_leds(
         &self,
         controller_id: u32,
-        colors: Vec<Color>,
+        colors: impl AsRef<[Color]>,
     ) -> Result<(), OpenRGBError> {
-        self.stream
-            .lock()
-            .await
-            .write_packet(
-                self.protocol,
-                controller_id,
-                RGBControllerUpdateLeds,
-                (colors.size(self.protocol), colors),
-            )
-            .await
+        let colors = colors.as_ref();
+        self.write_packet(
+            controller_id,
+            RGBControllerUpdateLeds,
+            (colors.size(self.get_protocol_version()), colors),
+        )
+        .await?;
+        let mut cache = self.shared.cache.lock().unwrap();
+        cache.set_colors(controller_id, 0, colors);
+        Ok(())
     }
 
     /// Update a zone LEDs.
     ///
+    /// Colors are borrowed, see [OpenRGB::update_leds].
+    ///
     /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_rgbcontroller_updatezoneleds) for more information.
     pub async fn update_zone_leds(
         &self,
         controller_id: u32,
         zone_id: u32,
-        colors: Vec<Color>,
+        colors: impl AsRef<[Color]>,
     ) -> Result<(), OpenRGBError> {
-        self.stream
-            .lock()
-            .await
-            .write_packet(
-                self.protocol,
-                controller_id,
-                RGBControllerUpdateZoneLeds,
-                (
-                    zone_id.size(self.protocol) + colors.size(self.protocol),
-                    zone_id,
-                    colors,
-                ),
-            )
-            .await
+        let colors = colors.as_ref();
+        self.write_packet(
+            controller_id,
+            RGBControllerUpdateZoneLeds,
+            (
+                zone_id.size(self.get_protocol_version())
+                    + colors.size(self.get_protocol_version()),
+                zone_id,
+                colors,
+            ),
+        )
+        .await?;
+        let mut cache = self.shared.cache.lock().unwrap();
+        cache.set_zone_colors(controller_id, zone_id, colors);
+        Ok(())
     }
 
     /// Get profiles.
@@ -241,10 +832,7 @@ impl<S: OpenRGBStream> OpenRGB<S> {
     /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_request_profile_list) for more information.
     pub async fn get_profiles(&self) -> Result<Vec<String>, OpenRGBError> {
         self.check_protocol_version_profile_control()?;
-        self.stream
-            .lock()
-            .await
-            .request::<_, (u32, Vec<String>)>(self.protocol, 0, RequestProfileList, ())
+        self.request::<_, (u32, Vec<String>)>(0, RequestProfileList, ())
             .await
             .map(|(_size, profiles)| profiles)
     }
@@ -254,11 +842,10 @@ impl<S: OpenRGBStream> OpenRGB<S> {
     /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_request_load_profile) for more information.
     pub async fn load_profile(&self, name: impl Into<String>) -> Result<(), OpenRGBError> {
         self.check_protocol_version_profile_control()?;
-        self.stream
-            .lock()
-            .await
-            .write_packet(self.protocol, 0, RequestLoadProfile, RawString(name.into()))
-            .await
+        self.write_packet(0, RequestLoadProfile, RawString(name.into()))
+            .await?;
+        self.invalidate_cache();
+        Ok(())
     }
 
     /// Save a profile.
@@ -266,11 +853,7 @@ impl<S: OpenRGBStream> OpenRGB<S> {
     /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_request_save_profile) for more information.
     pub async fn save_profile(&self, name: impl Into<String>) -> Result<(), OpenRGBError> {
         self.check_protocol_version_profile_control()?;
-        self.stream
-            .lock()
-            .await
-            .write_packet(self.protocol, 0, RequestSaveProfile, name.into())
-            .await
+        self.write_packet(0, RequestSaveProfile, name.into()).await
     }
 
     /// Delete a profile.
@@ -278,10 +861,7 @@ impl<S: OpenRGBStream> OpenRGB<S> {
     /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_request_delete_profile) for more information.
     pub async fn delete_profile(&self, name: impl Into<String>) -> Result<(), OpenRGBError> {
         self.check_protocol_version_profile_control()?;
-        self.stream
-            .lock()
-            .await
-            .write_packet(self.protocol, 0, RequestDeleteProfile, name.into())
+        self.write_packet(0, RequestDeleteProfile, name.into())
             .await
     }
 
@@ -289,11 +869,10 @@ impl<S: OpenRGBStream> OpenRGB<S> {
     ///
     /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_rgbcontroller_setcustommode) for more information.
     pub async fn set_custom_mode(&self, controller_id: u32) -> Result<(), OpenRGBError> {
-        self.stream
-            .lock()
-            .await
-            .write_packet(self.protocol, controller_id, RGBControllerSetCustomMode, ())
-            .await
+        self.write_packet(controller_id, RGBControllerSetCustomMode, ())
+            .await?;
+        self.shared.cache.lock().unwrap().remove(controller_id);
+        Ok(())
     }
 
     /// Update a mode.
@@ -305,20 +884,18 @@ impl<S: OpenRGBStream> OpenRGB<S> {
         mode_id: i32,
         mode: Mode,
     ) -> Result<(), OpenRGBError> {
-        self.stream
-            .lock()
-            .await
-            .write_packet(
-                self.protocol,
-                controller_id,
-                RGBControllerUpdateMode,
-                (
-                    mode_id.size(self.protocol) + mode.size(self.protocol),
-                    mode_id,
-                    mode,
-                ),
-            )
-            .await
+        self.write_packet(
+            controller_id,
+            RGBControllerUpdateMode,
+            (
+                mode_id.size(self.get_protocol_version()) + mode.size(self.get_protocol_version()),
+                mode_id,
+                mode,
+            ),
+        )
+        .await?;
+        self.shared.cache.lock().unwrap().remove(controller_id);
+        Ok(())
     }
 
     /// Save a mode.
@@ -326,18 +903,206 @@ impl<S: OpenRGBStream> OpenRGB<S> {
     /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_rgbcontroller_savemode) for more information.
     pub async fn save_mode(&self, controller_id: u32, mode: Mode) -> Result<(), OpenRGBError> {
         self.check_protocol_version_saving_modes()?;
-        self.stream
-            .lock()
+        self.write_packet(controller_id, RGBControllerSaveMode, mode)
+            .await
+    }
+
+    /// Get SDK plugins loaded by server.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_request_plugin_list) for more information.
+    pub async fn get_plugins(&self) -> Result<Vec<Plugin>, OpenRGBError> {
+        self.check_protocol_version_plugins()?;
+        self.request::<_, (u32, Vec<Plugin>)>(0, RequestPluginList, ())
             .await
-            .write_packet(self.protocol, controller_id, RGBControllerSaveMode, mode)
+            .map(|(_size, plugins)| plugins)
+    }
+
+    /// Send a plugin specific request, and return plugin reply.
+    ///
+    /// `plugin_id` is the plugin [index](Plugin::index). Request and reply data formats are
+    /// defined by the plugin.
+    ///
+    /// See [Open SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation#net_packet_id_plugin_specific) for more information.
+    pub async fn plugin_request(
+        &self,
+        plugin_id: u32,
+        data: &[u8],
+    ) -> Result<Vec<u8>, OpenRGBError> {
+        self.check_protocol_version_plugins()?;
+        let response = self
+            .queue_request(plugin_id, PluginSpecific, RawBytes(data))
+            .await?;
+        self.receive_payload(response).await.map(Vec::from)
+    }
+
+    /// Send a raw packet, without waiting for a reply.
+    ///
+    /// This allows experimenting with packets not supported by this crate yet.
+    pub async fn write_raw_packet(
+        &self,
+        device_id: u32,
+        packet_id: u32,
+        data: &[u8],
+    ) -> Result<(), OpenRGBError> {
+        self.write_packet(device_id, packet_id.into(), RawBytes(data))
             .await
     }
 
+    /// Send a raw packet, and return raw payload of reply with same device and packet IDs.
+    ///
+    /// This allows experimenting with packets not supported by this crate yet. Fails with
+    /// [OpenRGBError::InvalidArgument] for [PacketId::DeviceListUpdated], as packets with this ID
+    /// are published as [OpenRGBEvent::DeviceListUpdated] instead.
+    pub async fn raw_request(
+        &self,
+        device_id: u32,
+        packet_id: u32,
+        data: &[u8],
+    ) -> Result<Vec<u8>, OpenRGBError> {
+        check_raw_request(packet_id)?;
+        let response = self
+            .queue_request(device_id, packet_id.into(), RawBytes(data))
+            .await?;
+        self.receive_payload(response).await.map(Vec::from)
+    }
+
+    async fn write_packet<I: OpenRGBWritable>(
+        &self,
+        device_id: u32,
+        packet_id: PacketId,
+        data: I,
+    ) -> Result<(), OpenRGBError> {
+        with_timeout(
+            self.shared.request_timeout,
+            self.send(device_id, packet_id, data, None),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// Cache a copy of fetched controller data, unless cache was invalidated since request.
+    fn cache_controller(&self, generation: u64, controller_id: u32, controller: &Controller) {
+        let mut cache = self.shared.cache.lock().unwrap();
+        cache.insert(generation, controller_id, controller.clone());
+    }
+
+    /// Stop expecting replies of cancelled requests with given packet ID.
+    ///
+    /// Replies of cancelled requests are normally still expected, so that they are not routed to
+    /// later requests. This is only correct when server may never reply, eg: for controllers removed
+    /// meanwhile, and a late reply then only reaches a later identical request.
+    fn forget_cancelled_requests(&self, packet_id: PacketId) {
+        let mut pending = self.shared.pending.lock().unwrap();
+        pending.replies.retain(|&(_, id), replies| {
+            if id == packet_id {
+                replies.retain(|reply| !reply.is_closed());
+            }
+            !replies.is_empty()
+        });
+    }
+
+    async fn request<I: OpenRGBWritable, O: OpenRGBReadable>(
+        &self,
+        device_id: u32,
+        packet_id: PacketId,
+        data: I,
+    ) -> Result<O, OpenRGBError> {
+        let response = self.queue_request(device_id, packet_id, data).await?;
+        self.receive(response).await
+    }
+
+    /// Queue request for writing, without waiting for its reply.
+    async fn queue_request<I: OpenRGBWritable>(
+        &self,
+        device_id: u32,
+        packet_id: PacketId,
+        data: I,
+    ) -> Result<PendingResponse, OpenRGBError> {
+        let (sender, reply) = oneshot::channel();
+        let protocol = with_timeout(
+            self.shared.request_timeout,
+            self.send(device_id, packet_id, data, Some(sender)),
+        )
+        .await?;
+        Ok(PendingResponse { protocol, reply })
+    }
+
+    /// Wait for a queued request reply.
+    async fn receive<O: OpenRGBReadable>(
+        &self,
+        response: PendingResponse,
+    ) -> Result<O, OpenRGBError> {
+        let protocol = response.protocol;
+        decode(
+            &self.receive_payload(response).await?,
+            protocol,
+            &self.shared.decode_limits,
+        )
+    }
+
+    /// Wait for a queued request reply, without decoding it.
+    async fn receive_payload(&self, response: PendingResponse) -> Result<Bytes, OpenRGBError> {
+        with_timeout(self.shared.request_timeout, async {
+            response.reply.await.map_err(|_| connection_closed())?
+        })
+        .await
+    }
+
+    /// Queue packet for writing, registering `reply` to receive its reply if any.
+    ///
+    /// Returns the protocol version the packet was encoded with.
+    async fn send<I: OpenRGBWritable>(
+        &self,
+        device_id: u32,
+        packet_id: PacketId,
+        data: I,
+        reply: Option<oneshot::Sender<Reply>>,
+    ) -> Result<u32, OpenRGBError> {
+        let protocol = self.get_protocol_version();
+        let mut packet = self
+            .shared
+            .buffers
+            .lock()
+            .unwrap()
+            .pop()
+            .unwrap_or_default();
+        encode_packet(&mut packet, protocol, device_id, packet_id, data)?;
+
+        let writer = self
+            .shared
+            .pending
+            .lock()
+            .unwrap()
+            .writer
+            .clone()
+            .ok_or_else(connection_closed)?;
+        let permit = writer.reserve().await.map_err(|_| connection_closed())?;
+
+        // packet and reply are queued together, so that replies to identical requests are received in queue order
+        let mut pending = self.shared.pending.lock().unwrap();
+        match &pending.writer {
+            // connection may have been lost or re-established while waiting
+            Some(current) if current.same_channel(&writer) => {}
+            _ => return Err(connection_closed()),
+        }
+        if let Some(reply) = reply {
+            pending
+                .replies
+                .entry((device_id, packet_id))
+                .or_default()
+                .push_back(reply);
+        }
+        permit.send(packet);
+
+        Ok(protocol)
+    }
+
     fn check_protocol_version_profile_control(&self) -> Result<(), OpenRGBError> {
-        if self.protocol < 2 {
+        let protocol = self.get_protocol_version();
+        if protocol < 2 {
             return Err(UnsupportedOperation {
                 operation: "Profile control".to_owned(),
-                current_protocol_version: self.protocol,
+                current_protocol_version: protocol,
                 min_protocol_version: 2,
             });
         }
@@ -345,24 +1110,331 @@ impl<S: OpenRGBStream> OpenRGB<S> {
     }
 
     fn check_protocol_version_saving_modes(&self) -> Result<(), OpenRGBError> {
-        if self.protocol < 3 {
+        let protocol = self.get_protocol_version();
+        if protocol < 3 {
             return Err(UnsupportedOperation {
                 operation: "Saving modes".to_owned(),
-                current_protocol_version: self.protocol,
+                current_protocol_version: protocol,
                 min_protocol_version: 3,
             });
         }
         Ok(())
     }
+
+    fn check_protocol_version_segments(&self) -> Result<(), OpenRGBError> {
+        let protocol = self.get_protocol_version();
+        if protocol < 4 {
+            return Err(UnsupportedOperation {
+                operation: "Segments".to_owned(),
+                current_protocol_version: protocol,
+                min_protocol_version: 4,
+            });
+        }
+        Ok(())
+    }
+
+    fn check_protocol_version_plugins(&self) -> Result<(), OpenRGBError> {
+        let protocol = self.get_protocol_version();
+        if protocol < 4 {
+            return Err(UnsupportedOperation {
+                operation: "Plugins".to_owned(),
+                current_protocol_version: protocol,
+                min_protocol_version: 4,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<S: OpenRGBStream> Drop for OpenRGB<S> {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+/// Fail with [OpenRGBError::Timeout] if `future` does not complete within `timeout`, if any.
+async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    future: impl Future<Output = Result<T, OpenRGBError>>,
+) -> Result<T, OpenRGBError> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, future)
+            .await
+            .unwrap_or(Err(Timeout(timeout))),
+        None => future.await,
+    }
+}
+
+/// Negotiate protocol version on a newly opened stream, up to `max_protocol`.
+async fn negotiate_protocol<S: OpenRGBStream>(
+    stream: &mut S,
+    max_protocol: u32,
+    limits: &DecodeLimits,
+) -> Result<u32, OpenRGBError> {
+    let protocol = max_protocol.min(
+        stream
+            .request(
+                max_protocol,
+                0,
+                RequestProtocolVersion,
+                max_protocol,
+                limits,
+            )
+            .await?,
+    );
+
+    debug!(
+        "Connected to OpenRGB server using protocol version {:?}",
+        protocol
+    );
+
+    Ok(protocol)
+}
+
+/// Read server packets, routing replies to pending request and publishing notifications as events.
+///
+/// When connection is lost, reconnect if client has a reconnection policy, otherwise stop.
+async fn read_loop<S: OpenRGBStream + 'static>(mut stream: ReadHalf<S>, shared: Arc<Shared<S>>) {
+    loop {
+        let mut frames = FramedRead::new(stream, OpenRGBCodec::with_limits(shared.decode_limits));
+        let error = read_packets(&mut frames, &shared).await;
+        debug!("Stopped reading from OpenRGB server: {}", error);
+        disconnect(&shared, error);
+
+        stream = match &shared.reconnect {
+            Some(reconnect) => match reconnect_loop(&shared, reconnect).await {
+                Some(stream) => stream,
+                None => return,
+            },
+            None => return,
+        }
+    }
+}
+
+/// Read server packets until connection fails.
+async fn read_packets<S: OpenRGBStream>(
+    frames: &mut FramedRead<ReadHalf<S>, OpenRGBCodec>,
+    shared: &Shared<S>,
+) -> OpenRGBError {
+    loop {
+        let (header, payload) = match frames.next().await {
+            Some(Ok(frame)) => frame,
+            Some(Err(error)) => return error,
+            None => return connection_closed(),
+        };
+
+        if header.packet_id == DeviceListUpdated {
+            debug!("Received {:?} notification", header.packet_id);
+            shared.cache.lock().unwrap().invalidate();
+            // no subscribers is not an error
+            let _ = shared.events.send(OpenRGBEvent::DeviceListUpdated);
+            continue;
+        }
+
+        let reply = {
+            let mut pending = shared.pending.lock().unwrap();
+            let key = (header.device_id, header.packet_id);
+            let reply = pending.replies.get_mut(&key).and_then(VecDeque::pop_front);
+            if pending.replies.get(&key).is_some_and(VecDeque::is_empty) {
+                pending.replies.remove(&key);
+            }
+            reply
+        };
+
+        match reply {
+            // replies of cancelled requests are still expected, and dropped here
+            Some(reply) => {
+                let _ = reply.send(Ok(payload));
+            }
+            None => warn!(
+                "Discarding unexpected {:?} packet for device {}",
+                header.packet_id, header.device_id
+            ),
+        }
+    }
+}
+
+/// Fail pending requests and reject new ones until connection is re-established.
+fn disconnect<S: OpenRGBStream>(shared: &Shared<S>, error: OpenRGBError) {
+    {
+        let mut pending = shared.pending.lock().unwrap();
+        pending.writer = None;
+        let mut error = Some(error);
+        for reply in pending.replies.drain().flat_map(|(_, replies)| replies) {
+            let _ = reply.send(Err(error.take().unwrap_or_else(connection_closed)));
+        }
+    }
+    // server may have restarted with other devices
+    shared.cache.lock().unwrap().invalidate();
+
+    let _ = shared.events.send(OpenRGBEvent::ConnectionLost);
+}
+
+/// Check whether a [OpenRGBEvent::DeviceListUpdated] event was received, or may have been missed.
+fn device_list_updated(events: &mut broadcast::Receiver<OpenRGBEvent>) -> bool {
+    loop {
+        match events.try_recv() {
+            Ok(OpenRGBEvent::DeviceListUpdated) | Err(TryRecvError::Lagged(_)) => return true,
+            Ok(_) => continue,
+            Err(TryRecvError::Empty) | Err(TryRecvError::Closed) => return false,
+        }
+    }
+}
+
+/// Wait for a [OpenRGBEvent::DeviceListUpdated] event, or for events to be missed.
+async fn next_device_list_update(events: &mut broadcast::Receiver<OpenRGBEvent>) {
+    loop {
+        match events.recv().await {
+            Ok(OpenRGBEvent::DeviceListUpdated) | Err(RecvError::Lagged(_)) => return,
+            Ok(_) => continue,
+            Err(RecvError::Closed) => std::future::pending().await,
+        }
+    }
+}
+
+/// Spawn a task writing queued packets to given stream, and giving their buffers back.
+///
+/// The task stops when the queue is closed, after writing all queued packets.
+fn spawn_writer<S: OpenRGBStream + 'static>(
+    mut stream: WriteHalf<S>,
+    buffers: Buffers,
+) -> mpsc::Sender<Vec<u8>> {
+    let (writer, mut packets) = mpsc::channel::<Vec<u8>>(WRITE_QUEUE_CAPACITY);
+    tokio::spawn(async move {
+        while let Some(mut packet) = packets.recv().await {
+            if let Err(error) = stream.write_all(&packet).await {
+                // reader fails as well, and handles disconnection
+                debug!("Stopped writing to OpenRGB server: {}", error);
+                return;
+            }
+            let mut buffers = buffers.lock().unwrap();
+            if buffers.len() < WRITE_QUEUE_CAPACITY {
+                packet.clear();
+                buffers.push(packet);
+            }
+        }
+    });
+    writer
+}
+
+/// Try to connect again according to reconnection policy, and restore session.
+///
+/// Returns the new stream read half, or `None` if all attempts failed.
+async fn reconnect_loop<S: OpenRGBStream + 'static>(
+    shared: &Shared<S>,
+    reconnect: &Reconnect<S>,
+) -> Option<ReadHalf<S>> {
+    for (attempt, delay) in reconnect.policy.delays().enumerate() {
+        tokio::time::sleep(delay).await;
+
+        debug!(
+            "Reconnecting to OpenRGB server (attempt {})...",
+            attempt + 1
+        );
+        let name = shared.name.lock().unwrap().clone();
+        let result = match (reconnect.connect)().await {
+            Ok(mut stream) => with_timeout(
+                shared.request_timeout,
+                open_session(
+                    &mut stream,
+                    shared.protocol_selection,
+                    name,
+                    &shared.decode_limits,
+                ),
+            )
+            .await
+            .map(|protocol| (stream, protocol)),
+            Err(error) => Err(error),
+        };
+
+        match result {
+            Ok((stream, protocol)) => {
+                let (reader, writer) = split(stream);
+                let previous = shared.protocol.swap(protocol, Ordering::Relaxed);
+                shared.pending.lock().unwrap().writer =
+                    Some(spawn_writer(writer, shared.buffers.clone()));
+
+                let _ = shared.events.send(OpenRGBEvent::Reconnected);
+                if protocol != previous {
+                    let _ = shared
+                        .events
+                        .send(OpenRGBEvent::ProtocolRenegotiated { protocol });
+                }
+
+                return Some(reader);
+            }
+            Err(error) => warn!("Failed reconnecting to OpenRGB server: {}", error),
+        }
+    }
+
+    warn!("Giving up reconnecting to OpenRGB server");
+    None
+}
+
+/// Select protocol version and set client name on a newly opened stream.
+async fn open_session<S: OpenRGBStream>(
+    stream: &mut S,
+    protocol: ProtocolSelection,
+    name: Option<String>,
+    limits: &DecodeLimits,
+) -> Result<u32, OpenRGBError> {
+    let protocol = match protocol {
+        ProtocolSelection::Negotiate(max_protocol) => {
+            negotiate_protocol(stream, max_protocol, limits).await?
+        }
+        ProtocolSelection::Force(protocol) => {
+            debug!("Using forced protocol version {:?}", protocol);
+            protocol
+        }
+    };
+    if let Some(name) = name {
+        stream
+            .write_packet(protocol, 0, SetClientName, RawString(name))
+            .await?;
+    }
+    Ok(protocol)
+}
+
+/// Check raw request packet ID, see [OpenRGB::raw_request].
+fn check_raw_request(packet_id: u32) -> Result<(), OpenRGBError> {
+    if PacketId::from(packet_id) == DeviceListUpdated {
+        return Err(InvalidArgument(format!(
+            "packet ID {} is reserved for device list notifications",
+            packet_id
+        )));
+    }
+    Ok(())
+}
+
+fn connection_closed() -> OpenRGBError {
+    CommunicationError {
+        source: std::io::Error::new(
+            ErrorKind::NotConnected,
+            "connection to OpenRGB server closed",
+        ),
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::VecDeque;
     use std::error::Error;
+    use std::sync::Mutex;
+    use std::time::Duration;
 
+    use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+    use tokio::time::timeout;
+    use tokio_stream::StreamExt;
     use tokio_test::io::Builder;
 
-    use crate::tests::{setup, OpenRGBMockBuilder};
+    use crate::client::connection_closed;
+    use crate::data::{
+        Color, Controller, DeviceKey, DeviceType, Plugin, Resolution, Segment, Zone, ZoneType, LED,
+    };
+    use crate::tests::{controller_reply, setup, OpenRGBMockBuilder};
+    use crate::{
+        OpenRGB, OpenRGBBuilder, OpenRGBError, OpenRGBEvent, ReconnectPolicy, DEFAULT_PROTOCOL,
+    };
 
     #[tokio::test]
     async fn test_negotiate_protocol_version_3() -> Result<(), Box<dyn Error>> {
@@ -406,55 +1478,1056 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore]
     async fn test_get_controller_count() -> Result<(), Box<dyn Error>> {
         setup()?;
 
-        let _client = Builder::new()
+        let client = Builder::new()
             .negotiate_default_protocol()
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&0_u32.to_le_bytes()) // packet id
+            .write(&0_u32.to_le_bytes()) // data size
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&0_u32.to_le_bytes()) // packet id
+            .read(&4_u32.to_le_bytes()) // data size
+            .read(&7_u32.to_le_bytes()) // count
             .to_client()
             .await?;
 
-        todo!("test not implemented")
+        assert_eq!(client.get_controller_count().await?, 7);
+
+        Ok(())
     }
 
     #[tokio::test]
-    #[ignore]
-    async fn test_get_controller() -> Result<(), Box<dyn Error>> {
+    async fn test_device_list_updated_between_requests() -> Result<(), Box<dyn Error>> {
         setup()?;
 
-        let _client = Builder::new()
+        let client = Builder::new()
             .negotiate_default_protocol()
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&0_u32.to_le_bytes()) // packet id
+            .write(&0_u32.to_le_bytes()) // data size
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&100_u32.to_le_bytes()) // packet id
+            .read(&0_u32.to_le_bytes()) // data size
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&0_u32.to_le_bytes()) // packet id
+            .read(&4_u32.to_le_bytes()) // data size
+            .read(&3_u32.to_le_bytes()) // count
             .to_client()
             .await?;
 
-        todo!("test not implemented")
+        let mut events = client.events();
+
+        assert_eq!(client.get_controller_count().await?, 3);
+        assert_eq!(events.recv().await?, OpenRGBEvent::DeviceListUpdated);
+
+        Ok(())
     }
 
     #[tokio::test]
-    #[ignore]
-    async fn test_update_zone_leds() -> Result<(), Box<dyn Error>> {
+    async fn test_connection_lost_event() -> Result<(), Box<dyn Error>> {
         setup()?;
 
-        let _client = Builder::new()
+        let client = Builder::new()
             .negotiate_default_protocol()
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&50_u32.to_le_bytes()) // packet id
+            .write(&5_u32.to_le_bytes()) // data size
+            .write(b"test\0") // name
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&100_u32.to_le_bytes()) // packet id
+            .read(&0_u32.to_le_bytes()) // data size
             .to_client()
             .await?;
 
-        todo!("test not implemented")
+        let mut events = client.event_stream();
+
+        client.set_name("test").await?;
+
+        assert_eq!(events.next().await, Some(OpenRGBEvent::DeviceListUpdated));
+        assert_eq!(events.next().await, Some(OpenRGBEvent::ConnectionLost));
+
+        Ok(())
     }
 
     #[tokio::test]
-    #[ignore]
-    async fn test_resize_zone() -> Result<(), Box<dyn Error>> {
+    async fn test_reconnect() -> Result<(), Box<dyn Error>> {
         setup()?;
 
-        let _client = Builder::new()
+        let streams = Mutex::new(VecDeque::from([
+            Builder::new()
+                .negotiate_default_protocol()
+                .write(b"ORGB") // magic
+                .write(&0_u32.to_le_bytes()) // device id
+                .write(&50_u32.to_le_bytes()) // packet id
+                .write(&5_u32.to_le_bytes()) // data size
+                .write(b"test\0") // name
+                .build(),
+            Builder::new()
+                .negotiate_protocol(2)
+                .write(b"ORGB") // magic
+                .write(&0_u32.to_le_bytes()) // device id
+                .write(&50_u32.to_le_bytes()) // packet id
+                .write(&5_u32.to_le_bytes()) // data size
+                .write(b"test\0") // name
+                .write(b"ORGB") // magic
+                .write(&0_u32.to_le_bytes()) // device id
+                .write(&0_u32.to_le_bytes()) // packet id
+                .write(&0_u32.to_le_bytes()) // data size
+                .read(b"ORGB") // magic
+                .read(&0_u32.to_le_bytes()) // device id
+                .read(&0_u32.to_le_bytes()) // packet id
+                .read(&4_u32.to_le_bytes()) // data size
+                .read(&5_u32.to_le_bytes()) // count
+                .build(),
+            Builder::new()
+                .negotiate_protocol(2)
+                .write(b"ORGB") // magic
+                .write(&0_u32.to_le_bytes()) // device id
+                .write(&50_u32.to_le_bytes()) // packet id
+                .write(&5_u32.to_le_bytes()) // data size
+                .write(b"test\0") // name
+                .build(),
+        ]));
+
+        let client = OpenRGB::with_reconnect(
+            move || {
+                let stream = streams.lock().unwrap().pop_front();
+                async move { stream.ok_or_else(connection_closed) }
+            },
+            ReconnectPolicy {
+                initial_delay: Duration::ZERO,
+                max_attempts: Some(1),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        let mut events = client.events();
+
+        client.set_name("test").await?;
+
+        assert_eq!(events.recv().await?, OpenRGBEvent::ConnectionLost);
+        assert_eq!(events.recv().await?, OpenRGBEvent::Reconnected);
+        assert_eq!(
+            events.recv().await?,
+            OpenRGBEvent::ProtocolRenegotiated { protocol: 2 }
+        );
+        assert_eq!(client.get_protocol_version(), 2);
+        assert_eq!(client.get_controller_count().await?, 5);
+
+        // same protocol version negotiated again
+        assert_eq!(events.recv().await?, OpenRGBEvent::ConnectionLost);
+        assert_eq!(events.recv().await?, OpenRGBEvent::Reconnected);
+        assert_eq!(events.recv().await?, OpenRGBEvent::ConnectionLost);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_connection_closed() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let client = Builder::new()
             .negotiate_default_protocol()
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&0_u32.to_le_bytes()) // packet id
+            .write(&0_u32.to_le_bytes()) // data size
             .to_client()
             .await?;
 
-        todo!("test not implemented")
+        assert!(matches!(
+            client.get_controller_count().await,
+            Err(OpenRGBError::CommunicationError { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_request() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let stream = Builder::new()
+            .negotiate_default_protocol()
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&0_u32.to_le_bytes()) // packet id
+            .write(&0_u32.to_le_bytes()) // data size
+            .wait(Duration::from_millis(100))
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&0_u32.to_le_bytes()) // packet id
+            .read(&4_u32.to_le_bytes()) // data size
+            .read(&5_u32.to_le_bytes()) // count
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&0_u32.to_le_bytes()) // packet id
+            .write(&0_u32.to_le_bytes()) // data size
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&0_u32.to_le_bytes()) // packet id
+            .read(&4_u32.to_le_bytes()) // data size
+            .read(&7_u32.to_le_bytes()) // count
+            .build();
+
+        let client = OpenRGBBuilder::new()
+            .request_timeout(Duration::from_millis(10))
+            .build(stream)
+            .await?;
+
+        assert!(matches!(
+            client.get_controller_count().await,
+            Err(OpenRGBError::Timeout(_))
+        ));
+
+        // let server catch up, late reply must not be received by next request
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        assert_eq!(client.get_controller_count().await?, 7);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pipelined_requests() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let client = Builder::new()
+            .negotiate_default_protocol()
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&0_u32.to_le_bytes()) // packet id
+            .write(&0_u32.to_le_bytes()) // data size
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&150_u32.to_le_bytes()) // packet id
+            .write(&0_u32.to_le_bytes()) // data size
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&150_u32.to_le_bytes()) // packet id
+            .read(&10_u32.to_le_bytes()) // data size
+            .read(&6_u32.to_le_bytes()) // profiles size
+            .read(&1_u16.to_le_bytes()) // profiles count
+            .read(&2_u16.to_le_bytes()) // profile name length
+            .read(b"a\0") // profile name
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&0_u32.to_le_bytes()) // packet id
+            .read(&4_u32.to_le_bytes()) // data size
+            .read(&7_u32.to_le_bytes()) // count
+            .to_client()
+            .await?;
+
+        let (count, profiles) = tokio::join!(client.get_controller_count(), client.get_profiles());
+
+        assert_eq!(count?, 7);
+        assert_eq!(profiles?, vec!["a".to_string()]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_all_controllers_count_changed() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let mut builder = Builder::new();
+        builder.negotiate_default_protocol();
+        for count in [0_u32, 1, 0, 0] {
+            builder
+                .write(b"ORGB") // magic
+                .write(&0_u32.to_le_bytes()) // device id
+                .write(&0_u32.to_le_bytes()) // packet id
+                .write(&0_u32.to_le_bytes()) // data size
+                .read(b"ORGB") // magic
+                .read(&0_u32.to_le_bytes()) // device id
+                .read(&0_u32.to_le_bytes()) // packet id
+                .read(&4_u32.to_le_bytes()) // data size
+                .read(&count.to_le_bytes()); // count
+        }
+        let client = builder.to_client().await?;
+
+        assert!(client.get_all_controllers().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_all_controllers_device_list_updated() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let mut builder = Builder::new();
+        builder.negotiate_default_protocol();
+        for device_list_updated in [true, false, false, false] {
+            builder
+                .write(b"ORGB") // magic
+                .write(&0_u32.to_le_bytes()) // device id
+                .write(&0_u32.to_le_bytes()) // packet id
+                .write(&0_u32.to_le_bytes()) // data size
+                .read(b"ORGB") // magic
+                .read(&0_u32.to_le_bytes()) // device id
+                .read(&0_u32.to_le_bytes()) // packet id
+                .read(&4_u32.to_le_bytes()) // data size
+                .read(&0_u32.to_le_bytes()); // count
+            if device_list_updated {
+                builder
+                    .read(b"ORGB") // magic
+                    .read(&0_u32.to_le_bytes()) // device id
+                    .read(&100_u32.to_le_bytes()) // packet id
+                    .read(&0_u32.to_le_bytes()); // data size
+            }
+        }
+        let client = builder.to_client().await?;
+
+        assert!(client.get_all_controllers().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_all_controllers_device_removed() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        fn count_request(builder: &mut Builder, count: u32) -> &mut Builder {
+            builder
+                .write(b"ORGB") // magic
+                .write(&0_u32.to_le_bytes()) // device id
+                .write(&0_u32.to_le_bytes()) // packet id
+                .write(&0_u32.to_le_bytes()) // data size
+                .read(b"ORGB") // magic
+                .read(&0_u32.to_le_bytes()) // device id
+                .read(&0_u32.to_le_bytes()) // packet id
+                .read(&4_u32.to_le_bytes()) // data size
+                .read(&count.to_le_bytes()) // count
+        }
+
+        fn controller_request(builder: &mut Builder, controller_id: u32) -> &mut Builder {
+            builder
+                .write(b"ORGB") // magic
+                .write(&controller_id.to_le_bytes()) // device id
+                .write(&1_u32.to_le_bytes()) // packet id
+                .write(&4_u32.to_le_bytes()) // data size
+                .write(&DEFAULT_PROTOCOL.to_le_bytes()) // protocol version
+        }
+
+        let mut builder = Builder::new();
+        builder.negotiate_default_protocol();
+        count_request(&mut builder, 2);
+        controller_request(&mut builder, 0);
+        controller_request(&mut builder, 1)
+            .read(&controller_packet(0, 5))
+            // controller 1 removed, server never replies for it
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&100_u32.to_le_bytes()) // packet id
+            .read(&0_u32.to_le_bytes()); // data size
+        count_request(&mut builder, 1);
+        controller_request(&mut builder, 0).read(&controller_packet(0, 5));
+        count_request(&mut builder, 1);
+        let client = builder.to_client().await?;
+
+        let controllers = timeout(Duration::from_secs(1), client.get_all_controllers()).await??;
+        assert_eq!(controllers.len(), 1);
+
+        Ok(())
+    }
+
+    /// Serve protocol negotiation then a controller count request on given stream.
+    async fn serve_controller_count(
+        mut server: impl AsyncRead + AsyncWrite + Unpin,
+        count: u32,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut request = [0; 20];
+        server.read_exact(&mut request).await?;
+        server.write_all(b"ORGB").await?; // magic
+        server.write_all(&0_u32.to_le_bytes()).await?; // device id
+        server.write_all(&40_u32.to_le_bytes()).await?; // packet id
+        server.write_all(&4_u32.to_le_bytes()).await?; // data size
+        server.write_all(&DEFAULT_PROTOCOL.to_le_bytes()).await?; // protocol version
+
+        let mut request = [0; 16];
+        server.read_exact(&mut request).await?;
+        server.write_all(b"ORGB").await?; // magic
+        server.write_all(&0_u32.to_le_bytes()).await?; // device id
+        server.write_all(&0_u32.to_le_bytes()).await?; // packet id
+        server.write_all(&4_u32.to_le_bytes()).await?; // data size
+        server.write_all(&count.to_le_bytes()).await?; // count
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duplex_stream() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let (stream, server) = tokio::io::duplex(1024);
+        let server = tokio::spawn(serve_controller_count(server, 3));
+
+        let client = OpenRGB::new(stream).await?;
+
+        assert_eq!(client.get_controller_count().await?, 3);
+        server.await?.map_err(|e| e as Box<dyn Error>)?;
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_connect_unix() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let path = std::env::temp_dir().join(format!("openrgb-test-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path)?;
+        let server = tokio::spawn(async move {
+            let (server, _) = listener.accept().await?;
+            serve_controller_count(server, 4).await
+        });
+
+        let client = OpenRGB::connect_unix(&path).await?;
+
+        assert_eq!(client.get_controller_count().await?, 4);
+        server.await?.map_err(|e| e as Box<dyn Error>)?;
+        std::fs::remove_file(&path)?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reply_trailing_bytes() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let client = Builder::new()
+            .negotiate_default_protocol()
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&0_u32.to_le_bytes()) // packet id
+            .write(&0_u32.to_le_bytes()) // data size
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&0_u32.to_le_bytes()) // packet id
+            .read(&8_u32.to_le_bytes()) // data size
+            .read(&7_u32.to_le_bytes()) // count
+            .read(&[1, 2, 3, 4]) // newer protocol field
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&0_u32.to_le_bytes()) // packet id
+            .write(&0_u32.to_le_bytes()) // data size
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&0_u32.to_le_bytes()) // packet id
+            .read(&4_u32.to_le_bytes()) // data size
+            .read(&5_u32.to_le_bytes()) // count
+            .to_client()
+            .await?;
+
+        assert_eq!(client.get_controller_count().await?, 7);
+        assert_eq!(client.get_controller_count().await?, 5);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reply_overread() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let client = Builder::new()
+            .negotiate_default_protocol()
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&0_u32.to_le_bytes()) // packet id
+            .write(&0_u32.to_le_bytes()) // data size
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&0_u32.to_le_bytes()) // packet id
+            .read(&2_u32.to_le_bytes()) // data size
+            .read(&[7, 0]) // truncated count
+            .to_client()
+            .await?;
+
+        assert!(matches!(
+            client.get_controller_count().await,
+            Err(OpenRGBError::ProtocolError(_))
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reply_exceeding_limits() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let client = Builder::new()
+            .negotiate_default_protocol()
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&0_u32.to_le_bytes()) // packet id
+            .write(&0_u32.to_le_bytes()) // data size
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&0_u32.to_le_bytes()) // packet id
+            .read(&u32::MAX.to_le_bytes()) // data size
+            .to_client()
+            .await?;
+
+        assert!(matches!(
+            client.get_controller_count().await,
+            Err(OpenRGBError::ProtocolError(_))
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_plugins() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let client = Builder::new()
+            .negotiate_default_protocol()
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&200_u32.to_le_bytes()) // packet id
+            .write(&0_u32.to_le_bytes()) // data size
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&200_u32.to_le_bytes()) // packet id
+            .read(&37_u32.to_le_bytes()) // data size
+            .read(&33_u32.to_le_bytes()) // plugins size
+            .read(&1_u16.to_le_bytes()) // plugins count
+            .read(&8_u16.to_le_bytes()) // name len
+            .read(b"Effects\0") // name
+            .read(&5_u16.to_le_bytes()) // description len
+            .read(b"test\0") // description
+            .read(&4_u16.to_le_bytes()) // version len
+            .read(b"1.0\0") // version
+            .read(&2_u32.to_le_bytes()) // index
+            .read(&1_u32.to_le_bytes()) // protocol version
+            .to_client()
+            .await?;
+
+        assert_eq!(
+            client.get_plugins().await?,
+            vec![Plugin {
+                name: "Effects".to_string(),
+                description: "test".to_string(),
+                version: "1.0".to_string(),
+                index: 2,
+                protocol_version: 1,
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_plugins_unsupported() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let client = Builder::new().negotiate_protocol(3).to_client().await?;
+
+        assert!(matches!(
+            client.get_plugins().await,
+            Err(OpenRGBError::UnsupportedOperation {
+                current_protocol_version: 3,
+                min_protocol_version: 4,
+                ..
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_plugin_request() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let client = Builder::new()
+            .negotiate_default_protocol()
+            .write(b"ORGB") // magic
+            .write(&2_u32.to_le_bytes()) // device id
+            .write(&201_u32.to_le_bytes()) // packet id
+            .write(&3_u32.to_le_bytes()) // data size
+            .write(&[1, 2, 3]) // request
+            .read(b"ORGB") // magic
+            .read(&2_u32.to_le_bytes()) // device id
+            .read(&201_u32.to_le_bytes()) // packet id
+            .read(&2_u32.to_le_bytes()) // data size
+            .read(&[4, 5]) // reply
+            .to_client()
+            .await?;
+
+        assert_eq!(client.plugin_request(2, &[1, 2, 3]).await?, vec![4, 5]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_raw_request() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let client = Builder::new()
+            .negotiate_default_protocol()
+            .write(b"ORGB") // magic
+            .write(&1_u32.to_le_bytes()) // device id
+            .write(&9999_u32.to_le_bytes()) // packet id
+            .write(&2_u32.to_le_bytes()) // data size
+            .write(&[1, 2]) // request
+            .read(b"ORGB") // magic
+            .read(&1_u32.to_le_bytes()) // device id
+            .read(&9999_u32.to_le_bytes()) // packet id
+            .read(&3_u32.to_le_bytes()) // data size
+            .read(&[3, 4, 5]) // reply
+            .to_client()
+            .await?;
+
+        assert_eq!(client.raw_request(1, 9999, &[1, 2]).await?, vec![3, 4, 5]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_raw_request_device_list_updated() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let client = Builder::new()
+            .negotiate_default_protocol()
+            .to_client()
+            .await?;
+
+        assert!(matches!(
+            client.raw_request(0, 100, &[]).await,
+            Err(OpenRGBError::InvalidArgument(_))
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unknown_packet_discarded() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let client = Builder::new()
+            .negotiate_default_protocol()
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&0_u32.to_le_bytes()) // packet id
+            .write(&0_u32.to_le_bytes()) // data size
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&9999_u32.to_le_bytes()) // packet id
+            .read(&3_u32.to_le_bytes()) // data size
+            .read(&[1, 2, 3]) // unknown data
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&0_u32.to_le_bytes()) // packet id
+            .read(&4_u32.to_le_bytes()) // data size
+            .read(&3_u32.to_le_bytes()) // count
+            .to_client()
+            .await?;
+
+        assert_eq!(client.get_controller_count().await?, 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_clear_segments() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let client = Builder::new()
+            .negotiate_default_protocol()
+            .write(b"ORGB") // magic
+            .write(&3_u32.to_le_bytes()) // device id
+            .write(&1001_u32.to_le_bytes()) // packet id
+            .write(&4_u32.to_le_bytes()) // data size
+            .write(&1_u32.to_le_bytes()) // zone id
+            .to_client()
+            .await?;
+
+        client.clear_segments(3, 1).await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_add_segment() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let client = Builder::new()
+            .negotiate_default_protocol()
+            .write(b"ORGB") // magic
+            .write(&3_u32.to_le_bytes()) // device id
+            .write(&1002_u32.to_le_bytes()) // packet id
+            .write(&28_u32.to_le_bytes()) // data size
+            .write(&24_u32.to_le_bytes()) // segment data size
+            .write(&1_u32.to_le_bytes()) // zone id
+            .write(&6_u16.to_le_bytes()) // name len
+            .write(b"Fan 2\0") // name
+            .write(&1_u32.to_le_bytes()) // type
+            .write(&12_u32.to_le_bytes()) // start_idx
+            .write(&12_u32.to_le_bytes()) // leds_count
+            .to_client()
+            .await?;
+
+        client
+            .add_segment(
+                3,
+                1,
+                Segment {
+                    name: "Fan 2".to_string(),
+                    r#type: ZoneType::Linear,
+                    start_idx: 12,
+                    leds_count: 12,
+                },
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_add_segment_unsupported() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let client = Builder::new().negotiate_protocol(3).to_client().await?;
+
+        assert!(matches!(
+            client
+                .add_segment(
+                    3,
+                    1,
+                    Segment {
+                        name: "Fan 2".to_string(),
+                        r#type: ZoneType::Linear,
+                        start_idx: 12,
+                        leds_count: 12,
+                    },
+                )
+                .await,
+            Err(OpenRGBError::UnsupportedOperation {
+                current_protocol_version: 3,
+                min_protocol_version: 4,
+                ..
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_get_controller() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let _client = Builder::new()
+            .negotiate_default_protocol()
+            .to_client()
+            .await?;
+
+        todo!("test not implemented")
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_update_zone_leds() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let _client = Builder::new()
+            .negotiate_default_protocol()
+            .to_client()
+            .await?;
+
+        todo!("test not implemented")
+    }
+
+    /// Controller data reply, for a controller with a single linear zone of given LED count.
+    fn controller_packet(controller_id: u32, leds_count: u32) -> Vec<u8> {
+        let controller = Controller {
+            r#type: DeviceType::LEDStrip,
+            name: "Test Strip".into(),
+            vendor: "".into(),
+            description: "".into(),
+            version: "".into(),
+            serial: "".into(),
+            location: "".into(),
+            active_mode: 0,
+            modes: vec![],
+            zones: vec![Zone {
+                name: "Strip".into(),
+                r#type: ZoneType::Linear,
+                leds_min: 1,
+                leds_max: 40,
+                leds_count,
+                matrix: None,
+                segments: vec![],
+            }],
+            leds: vec![],
+            colors: vec![],
+        };
+        controller_reply(controller_id, controller)
+    }
+
+    #[tokio::test]
+    async fn test_resize_zone() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let client = Builder::new()
+            .negotiate_default_protocol()
+            .write(b"ORGB") // magic
+            .write(&2_u32.to_le_bytes()) // device id
+            .write(&1_u32.to_le_bytes()) // packet id
+            .write(&4_u32.to_le_bytes()) // data size
+            .write(&DEFAULT_PROTOCOL.to_le_bytes()) // protocol version
+            .read(&controller_packet(2, 10))
+            .write(b"ORGB") // magic
+            .write(&2_u32.to_le_bytes()) // device id
+            .write(&1000_u32.to_le_bytes()) // packet id
+            .write(&8_u32.to_le_bytes()) // data size
+            .write(&0_u32.to_le_bytes()) // zone id
+            .write(&20_u32.to_le_bytes()) // new size
+            .write(b"ORGB") // magic
+            .write(&2_u32.to_le_bytes()) // device id
+            .write(&1_u32.to_le_bytes()) // packet id
+            .write(&4_u32.to_le_bytes()) // data size
+            .write(&DEFAULT_PROTOCOL.to_le_bytes()) // protocol version
+            .read(&controller_packet(2, 20))
+            .to_client()
+            .await?;
+
+        let controller = client.resize_zone(2, 0, 20).await?;
+        assert_eq!(controller.zones[0].leds_count, 20);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_devices() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let mut builder = Builder::new();
+        builder.negotiate_default_protocol();
+        builder
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&0_u32.to_le_bytes()) // packet id
+            .write(&0_u32.to_le_bytes()) // data size
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&0_u32.to_le_bytes()) // packet id
+            .read(&4_u32.to_le_bytes()) // data size
+            .read(&2_u32.to_le_bytes()); // count
+        for controller_id in 0..2_u32 {
+            builder
+                .write(b"ORGB") // magic
+                .write(&controller_id.to_le_bytes()) // device id
+                .write(&1_u32.to_le_bytes()) // packet id
+                .write(&4_u32.to_le_bytes()) // data size
+                .write(&DEFAULT_PROTOCOL.to_le_bytes()); // protocol version
+        }
+        builder
+            .read(&controller_packet(0, 10))
+            .read(&controller_packet(1, 20))
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&0_u32.to_le_bytes()) // packet id
+            .write(&0_u32.to_le_bytes()) // data size
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&0_u32.to_le_bytes()) // packet id
+            .read(&4_u32.to_le_bytes()) // data size
+            .read(&2_u32.to_le_bytes()); // count
+        let client = builder.to_client().await?;
+
+        let strip = DeviceKey {
+            r#type: DeviceType::LEDStrip,
+            vendor: "".into(),
+            name: "Test Strip".into(),
+            serial: "".into(),
+            location: "".into(),
+        };
+        let mouse = DeviceKey {
+            r#type: DeviceType::Mouse,
+            ..strip.clone()
+        };
+        assert_eq!(
+            client.resolve_devices([&strip, &mouse]).await?,
+            [Resolution::Ambiguous(vec![0, 1]), Resolution::Missing]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resize_zone_out_of_bounds() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let client = Builder::new()
+            .negotiate_default_protocol()
+            .write(b"ORGB") // magic
+            .write(&2_u32.to_le_bytes()) // device id
+            .write(&1_u32.to_le_bytes()) // packet id
+            .write(&4_u32.to_le_bytes()) // data size
+            .write(&DEFAULT_PROTOCOL.to_le_bytes()) // protocol version
+            .read(&controller_packet(2, 10))
+            .to_client()
+            .await?;
+
+        assert!(matches!(
+            client.resize_zone(2, 0, 41).await,
+            Err(OpenRGBError::InvalidArgument(_))
+        ));
+
+        Ok(())
+    }
+
+    /// Controller data reply, for a controller with a single linear zone of given colors.
+    fn colors_packet(controller_id: u32, colors: Vec<Color>) -> Vec<u8> {
+        let controller = Controller {
+            r#type: DeviceType::LEDStrip,
+            name: "Test Strip".into(),
+            vendor: "".into(),
+            description: "".into(),
+            version: "".into(),
+            serial: "".into(),
+            location: "".into(),
+            active_mode: 0,
+            modes: vec![],
+            zones: vec![Zone {
+                name: "Strip".into(),
+                r#type: ZoneType::Linear,
+                leds_min: 1,
+                leds_max: 40,
+                leds_count: colors.len() as u32,
+                matrix: None,
+                segments: vec![],
+            }],
+            leds: (0..colors.len() as u32)
+                .map(|value| LED {
+                    name: format!("LED {}", value),
+                    value,
+                })
+                .collect(),
+            colors,
+        };
+        controller_reply(controller_id, controller)
+    }
+
+    #[tokio::test]
+    async fn test_cached_controller() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let client = Builder::new()
+            .negotiate_default_protocol()
+            .write(b"ORGB") // magic
+            .write(&2_u32.to_le_bytes()) // device id
+            .write(&1_u32.to_le_bytes()) // packet id
+            .write(&4_u32.to_le_bytes()) // data size
+            .write(&DEFAULT_PROTOCOL.to_le_bytes()) // protocol version
+            .read(&colors_packet(2, vec![Color::default(); 3]))
+            .write(b"ORGB") // magic
+            .write(&2_u32.to_le_bytes()) // device id
+            .write(&1050_u32.to_le_bytes()) // packet id
+            .write(&18_u32.to_le_bytes()) // data size
+            .write(&14_u32.to_le_bytes()) // colors data size
+            .write(&3_u16.to_le_bytes()) // colors count
+            .write(&[1, 1, 1, 0]) // color 1
+            .write(&[1, 1, 1, 0]) // color 2
+            .write(&[1, 1, 1, 0]) // color 3
+            .write(b"ORGB") // magic
+            .write(&2_u32.to_le_bytes()) // device id
+            .write(&1052_u32.to_le_bytes()) // packet id
+            .write(&8_u32.to_le_bytes()) // data size
+            .write(&2_i32.to_le_bytes()) // led id
+            .write(&[2, 2, 2, 0]) // color
+            // round trip, failing if previous writes did not match
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&0_u32.to_le_bytes()) // packet id
+            .write(&0_u32.to_le_bytes()) // data size
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&0_u32.to_le_bytes()) // packet id
+            .read(&4_u32.to_le_bytes()) // data size
+            .read(&3_u32.to_le_bytes()) // count
+            .to_client()
+            .await?;
+
+        assert_eq!(client.get_cached_colors(2), None);
+        assert_eq!(client.get_cached_controller(2).await?.leds.len(), 3);
+        assert_eq!(client.get_cached_controller(2).await?.leds.len(), 3);
+
+        client.update_leds(2, [Color::new(1, 1, 1); 3]).await?;
+        client.update_led(2, 2, Color::new(2, 2, 2)).await?;
+        assert_eq!(
+            client.get_cached_colors(2),
+            Some(vec![
+                Color::new(1, 1, 1),
+                Color::new(1, 1, 1),
+                Color::new(2, 2, 2)
+            ])
+        );
+        assert_eq!(
+            timeout(Duration::from_secs(1), client.get_controller_count()).await??,
+            3
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_refresh_colors() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let colors = vec![Color::new(1, 2, 3), Color::new(4, 5, 6)];
+        let client = Builder::new()
+            .negotiate_default_protocol()
+            .write(b"ORGB") // magic
+            .write(&2_u32.to_le_bytes()) // device id
+            .write(&1_u32.to_le_bytes()) // packet id
+            .write(&4_u32.to_le_bytes()) // data size
+            .write(&DEFAULT_PROTOCOL.to_le_bytes()) // protocol version
+            .read(&colors_packet(2, vec![Color::default(); 2]))
+            .write(b"ORGB") // magic
+            .write(&2_u32.to_le_bytes()) // device id
+            .write(&1_u32.to_le_bytes()) // packet id
+            .write(&4_u32.to_le_bytes()) // data size
+            .write(&DEFAULT_PROTOCOL.to_le_bytes()) // protocol version
+            .read(&colors_packet(2, colors.clone()))
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&0_u32.to_le_bytes()) // packet id
+            .write(&0_u32.to_le_bytes()) // data size
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&0_u32.to_le_bytes()) // packet id
+            .read(&4_u32.to_le_bytes()) // data size
+            .read(&3_u32.to_le_bytes()) // count
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&100_u32.to_le_bytes()) // packet id
+            .read(&0_u32.to_le_bytes()) // data size
+            .to_client()
+            .await?;
+
+        let mut events = client.events();
+        client.get_controller(2).await?;
+        assert_eq!(client.refresh_colors(2).await?, colors);
+        assert_eq!(client.get_cached_colors(2), Some(colors));
+        assert_eq!(client.get_controller_count().await?, 3);
+
+        assert_eq!(events.recv().await?, OpenRGBEvent::DeviceListUpdated);
+        assert_eq!(client.get_cached_colors(2), None);
+
+        Ok(())
     }
 
     #[tokio::test]
@@ -0,0 +1,217 @@
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use openrgb_data::{OpenRGBWritableSync, WriteVec};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::data::DecodeLimits;
+use crate::protocol::{decode, HEADER_SIZE};
+use crate::{Header, OpenRGBError, DEFAULT_PROTOCOL};
+
+/// Codec splitting a byte stream into OpenRGB packets, as `(header, payload)` frames.
+///
+/// Use it with [tokio_util::codec::Framed] over any [AsyncRead](tokio::io::AsyncRead) +
+/// [AsyncWrite](tokio::io::AsyncWrite) stream, for example to build proxies or sniffers.
+/// Payloads are not decoded, since their format depends on packet ID and protocol version.
+///
+/// # Example
+///
+/// ```no_run
+/// # use openrgb::OpenRGBCodec;
+/// # use std::error::Error;
+/// # use tokio::net::TcpStream;
+/// # use tokio_stream::StreamExt;
+/// # use tokio_util::codec::FramedRead;
+/// #
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn Error>> {
+/// let stream = TcpStream::connect("localhost:6742").await?;
+/// let mut frames = FramedRead::new(stream, OpenRGBCodec::new());
+/// while let Some((header, payload)) = frames.next().await.transpose()? {
+///     println!("{:?}: {} bytes", header.packet_id, payload.len());
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct OpenRGBCodec {
+    limits: DecodeLimits,
+}
+
+impl OpenRGBCodec {
+    /// Create a new codec with default [DecodeLimits].
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create a new codec rejecting packets larger than given limits allow.
+    pub fn with_limits(limits: DecodeLimits) -> Self {
+        Self { limits }
+    }
+}
+
+impl Decoder for OpenRGBCodec {
+    type Item = (Header, Bytes);
+    type Error = OpenRGBError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if src.len() < HEADER_SIZE {
+            src.reserve(HEADER_SIZE - src.len());
+            return Ok(None);
+        }
+
+        // header format does not depend on protocol version
+        let header: Header = decode(&src[..HEADER_SIZE], DEFAULT_PROTOCOL, &self.limits)?;
+        let len = header.len as usize;
+        if len > self.limits.max_packet_size {
+            return Err(OpenRGBError::ProtocolError(format!(
+                "received packet of {} bytes, exceeding limit of {}",
+                len, self.limits.max_packet_size
+            )));
+        }
+
+        if src.len() < HEADER_SIZE + len {
+            src.reserve(HEADER_SIZE + len - src.len());
+            return Ok(None);
+        }
+
+        src.advance(HEADER_SIZE);
+        Ok(Some((header, src.split_to(len).freeze())))
+    }
+}
+
+impl Encoder<(Header, Bytes)> for OpenRGBCodec {
+    type Error = OpenRGBError;
+
+    /// Encode given frame, using payload length instead of [Header::len].
+    fn encode(&mut self, item: (Header, Bytes), dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let (header, payload) = item;
+        let mut buf = Vec::with_capacity(HEADER_SIZE);
+        WriteVec::new(&mut buf).write_header(
+            DEFAULT_PROTOCOL,
+            header.device_id,
+            header.packet_id,
+            payload.len(),
+        )?;
+        dst.reserve(HEADER_SIZE + payload.len());
+        dst.put_slice(&buf);
+        dst.put_slice(&payload);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use bytes::{Bytes, BytesMut};
+    use tokio_stream::StreamExt;
+    use tokio_test::io::Builder;
+    use tokio_util::codec::{Decoder, Encoder, FramedRead};
+
+    use crate::data::{DecodeLimits, PacketId};
+    use crate::tests::setup;
+    use crate::{Header, OpenRGBCodec, OpenRGBError};
+
+    #[tokio::test]
+    async fn test_framed_read() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let stream = Builder::new()
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&100_u32.to_le_bytes()) // packet id
+            .read(&0_u32.to_le_bytes()) // data size
+            .read(b"ORGB") // magic
+            .read(&3_u32.to_le_bytes()) // device id
+            .read(&1_u32.to_le_bytes()) // packet id
+            .read(&2_u32.to_le_bytes()) // data size
+            .read(&[1, 2]) // data
+            .build();
+
+        let mut frames = FramedRead::new(stream, OpenRGBCodec::new());
+
+        let (header, payload) = frames.next().await.unwrap()?;
+        assert_eq!(header.device_id, 0);
+        assert_eq!(header.packet_id, PacketId::DeviceListUpdated);
+        assert!(payload.is_empty());
+
+        let (header, payload) = frames.next().await.unwrap()?;
+        assert_eq!(header.device_id, 3);
+        assert_eq!(header.packet_id, PacketId::RequestControllerData);
+        assert_eq!(&payload[..], &[1, 2]);
+
+        assert!(frames.next().await.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_partial() -> Result<(), Box<dyn Error>> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"ORGB"); // magic
+        buf.extend_from_slice(&0_u32.to_le_bytes()); // device id
+        buf.extend_from_slice(&0_u32.to_le_bytes()); // packet id
+
+        assert!(OpenRGBCodec::new().decode(&mut buf)?.is_none());
+
+        buf.extend_from_slice(&4_u32.to_le_bytes()); // data size
+        buf.extend_from_slice(&[5, 0]); // partial count
+
+        assert!(OpenRGBCodec::new().decode(&mut buf)?.is_none());
+
+        buf.extend_from_slice(&[0, 0]); // rest of count
+
+        let (header, payload) = OpenRGBCodec::new().decode(&mut buf)?.unwrap();
+        assert_eq!(header.packet_id, PacketId::RequestControllerCount);
+        assert_eq!(&payload[..], &5_u32.to_le_bytes());
+        assert!(buf.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_exceeding_limits() {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"ORGB"); // magic
+        buf.extend_from_slice(&0_u32.to_le_bytes()); // device id
+        buf.extend_from_slice(&0_u32.to_le_bytes()); // packet id
+        buf.extend_from_slice(&5_u32.to_le_bytes()); // data size
+
+        let mut codec = OpenRGBCodec::with_limits(DecodeLimits {
+            max_packet_size: 4,
+            ..DecodeLimits::default()
+        });
+
+        assert!(matches!(
+            codec.decode(&mut buf),
+            Err(OpenRGBError::ProtocolError(_))
+        ));
+    }
+
+    #[test]
+    fn test_encode() -> Result<(), Box<dyn Error>> {
+        let mut buf = BytesMut::new();
+
+        OpenRGBCodec::new().encode(
+            (
+                Header {
+                    device_id: 2,
+                    packet_id: PacketId::RGBControllerUpdateLeds,
+                    ..Default::default()
+                },
+                Bytes::from_static(&[1, 2, 3]),
+            ),
+            &mut buf,
+        )?;
+
+        let mut expected = Vec::new();
+        expected.extend_from_slice(b"ORGB"); // magic
+        expected.extend_from_slice(&2_u32.to_le_bytes()); // device id
+        expected.extend_from_slice(&1050_u32.to_le_bytes()); // packet id
+        expected.extend_from_slice(&3_u32.to_le_bytes()); // data size
+        expected.extend_from_slice(&[1, 2, 3]); // data
+        assert_eq!(&buf[..], &expected[..]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,13 @@
+//! OpenRGB data types.
+//!
+//! See [OpenRGB SDK documentation](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation) for more information.
+//!
+//! These types are shared with the [openrgb_data] crate, which implements the wire format.
+
+pub use openrgb_data::{
+    Color, ColorMode, Controller, DecodeLimits, DeviceKey, DeviceResolver, DeviceType, Direction,
+    Matrix, Mode, ModeFlag, Plugin, Resolution, Segment, Zone, ZoneType, LED,
+};
+
+#[doc(hidden)]
+pub use openrgb_data::{OpenRGBReadable, OpenRGBWritable, PacketId, RawBytes, RawString};
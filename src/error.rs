@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use thiserror::Error;
 
 /// Errors returned by [OpenRGB client](crate::OpenRGB).
@@ -28,6 +30,14 @@ pub enum OpenRGBError {
     #[error("Invalid data encountered while communicating with OpenRGB server: {0}")]
     ProtocolError(String),
 
+    /// OpenRGB server did not respond in time.
+    #[error("Timed out after {0:?} waiting for OpenRGB server")]
+    Timeout(Duration),
+
+    /// Invalid argument given to a client method.
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
     /// Server does not support operation.
     #[error("{operation:?} is only supported since protocol version {min_protocol_version:?}, but version {current_protocol_version:?} is in use. Try upgrading the OpenRGB server.")]
     UnsupportedOperation {
@@ -42,3 +52,23 @@ pub enum OpenRGBError {
         min_protocol_version: u32,
     },
 }
+
+impl From<openrgb_data::OpenRGBError> for OpenRGBError {
+    fn from(error: openrgb_data::OpenRGBError) -> Self {
+        match error {
+            openrgb_data::OpenRGBError::UnsupportedOperation {
+                operation,
+                current_protocol_version,
+                min_protocol_version,
+            } => OpenRGBError::UnsupportedOperation {
+                operation,
+                current_protocol_version,
+                min_protocol_version,
+            },
+            openrgb_data::OpenRGBError::InvalidArgument(message) => {
+                OpenRGBError::InvalidArgument(message)
+            }
+            error => OpenRGBError::ProtocolError(error.to_string()),
+        }
+    }
+}
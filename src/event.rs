@@ -0,0 +1,24 @@
+/// Events published by [OpenRGB client](crate::OpenRGB).
+///
+/// See [OpenRGB::events](crate::OpenRGB::events) to subscribe to events.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum OpenRGBEvent {
+    /// Server indicated that its device list has been updated (eg: a device was plugged in).
+    ///
+    /// Controller IDs may have changed, controllers data should be fetched again.
+    DeviceListUpdated,
+
+    /// Connection to server was lost.
+    ///
+    /// Requests fail until connection is re-established.
+    ConnectionLost,
+
+    /// Connection to server was re-established.
+    Reconnected,
+
+    /// Protocol version negotiated again with server changed, after connection was re-established.
+    ProtocolRenegotiated {
+        /// Protocol version now in use.
+        protocol: u32,
+    },
+}
@@ -0,0 +1,379 @@
+use crate::data::{Color, Controller, Zone, LED};
+use crate::{OpenRGB, OpenRGBError, OpenRGBStream};
+
+use OpenRGBError::InvalidArgument;
+
+/// Controller of an [OpenRGB client](OpenRGB), with its data as fetched when handle was obtained.
+///
+/// Obtained with [OpenRGB::controller] or [OpenRGB::controllers]. Zones and LEDs are looked up by
+/// name in controller data, and their IDs and offsets computed from it. Controller data may be
+/// outdated after the server device list changes, see [ControllerHandle::refresh].
+///
+/// # Example
+///
+/// ```no_run
+/// # use openrgb::{data::Color, OpenRGB};
+/// # use std::error::Error;
+/// #
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn Error>> {
+/// let client = OpenRGB::connect().await?;
+///
+/// let controller = client.controller(3).await?;
+/// controller.zone("Riing Channel 1")?.set_all(Color::new(255, 0, 0)).await?;
+/// controller.led("Key: Escape")?.set(Color::new(0, 0, 255)).await?;
+/// #
+/// # Ok(())
+/// # }
+/// ```
+pub struct ControllerHandle<'a, S: OpenRGBStream> {
+    client: &'a OpenRGB<S>,
+    id: u32,
+    data: Controller,
+}
+
+impl<'a, S: OpenRGBStream + 'static> ControllerHandle<'a, S> {
+    pub(crate) fn new(client: &'a OpenRGB<S>, id: u32, data: Controller) -> Self {
+        Self { client, id, data }
+    }
+
+    /// Controller ID.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Controller data.
+    pub fn data(&self) -> &Controller {
+        &self.data
+    }
+
+    /// Controller name.
+    pub fn name(&self) -> &str {
+        &self.data.name
+    }
+
+    /// Fetch controller data again.
+    pub async fn refresh(&mut self) -> Result<(), OpenRGBError> {
+        self.data = self.client.get_controller(self.id).await?;
+        Ok(())
+    }
+
+    /// Get zone with given name.
+    pub fn zone(&self, name: &str) -> Result<ZoneHandle<'_, S>, OpenRGBError> {
+        self.zones()
+            .find(|zone| zone.name() == name)
+            .ok_or_else(|| {
+                InvalidArgument(format!(
+                    "no zone {:?} in controller {:?}",
+                    name,
+                    self.name()
+                ))
+            })
+    }
+
+    /// Get zone with given ID.
+    pub fn zone_by_id(&self, zone_id: u32) -> Result<ZoneHandle<'_, S>, OpenRGBError> {
+        self.zones().nth(zone_id as usize).ok_or_else(|| {
+            InvalidArgument(format!(
+                "no zone {} in controller {:?}",
+                zone_id,
+                self.name()
+            ))
+        })
+    }
+
+    /// Get all zones.
+    pub fn zones(&self) -> impl Iterator<Item = ZoneHandle<'_, S>> {
+        self.data
+            .zones
+            .iter()
+            .scan(0, |offset, zone| {
+                let zone_offset = *offset;
+                *offset += zone.leds_count;
+                Some(zone_offset)
+            })
+            .enumerate()
+            .map(move |(id, offset)| ZoneHandle {
+                controller: self,
+                id: id as u32,
+                offset,
+            })
+    }
+
+    /// Get LED with given name.
+    pub fn led(&self, name: &str) -> Result<LedHandle<'_, S>, OpenRGBError> {
+        self.leds().find(|led| led.name() == name).ok_or_else(|| {
+            InvalidArgument(format!("no LED {:?} in controller {:?}", name, self.name()))
+        })
+    }
+
+    /// Get all LEDs.
+    pub fn leds(&self) -> impl Iterator<Item = LedHandle<'_, S>> {
+        (0..self.data.leds.len()).map(move |id| LedHandle {
+            controller: self,
+            id: id as u32,
+        })
+    }
+
+    /// Set all controller LEDs to given color.
+    pub async fn set_all(&self, color: Color) -> Result<(), OpenRGBError> {
+        self.set_leds(vec![color; self.data.leds.len()]).await
+    }
+
+    /// Set controller LEDs colors, in LED order.
+    pub async fn set_leds(&self, colors: impl AsRef<[Color]>) -> Result<(), OpenRGBError> {
+        self.client.update_leds(self.id, colors).await
+    }
+}
+
+/// Zone of a [ControllerHandle].
+pub struct ZoneHandle<'a, S: OpenRGBStream> {
+    controller: &'a ControllerHandle<'a, S>,
+    id: u32,
+    offset: u32,
+}
+
+impl<'a, S: OpenRGBStream + 'static> ZoneHandle<'a, S> {
+    /// Zone ID.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Zone data.
+    pub fn data(&self) -> &'a Zone {
+        &self.controller.data.zones[self.id as usize]
+    }
+
+    /// Zone name.
+    pub fn name(&self) -> &'a str {
+        &self.data().name
+    }
+
+    /// Index of zone first LED in controller LEDs.
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Number of LEDs in zone.
+    pub fn len(&self) -> u32 {
+        self.data().leds_count
+    }
+
+    /// Whether zone has no LEDs.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get zone LED at given index, relative to zone first LED.
+    pub fn led(&self, index: u32) -> Result<LedHandle<'a, S>, OpenRGBError> {
+        if index >= self.len() {
+            return Err(InvalidArgument(format!(
+                "no LED {} in zone {:?} of {} LEDs",
+                index,
+                self.name(),
+                self.len()
+            )));
+        }
+        Ok(LedHandle {
+            controller: self.controller,
+            id: self.offset + index,
+        })
+    }
+
+    /// Get all zone LEDs.
+    pub fn leds(&self) -> impl Iterator<Item = LedHandle<'a, S>> {
+        let controller = self.controller;
+        (self.offset..self.offset + self.len()).map(move |id| LedHandle { controller, id })
+    }
+
+    /// Set all zone LEDs to given color.
+    pub async fn set_all(&self, color: Color) -> Result<(), OpenRGBError> {
+        self.set_leds(vec![color; self.len() as usize]).await
+    }
+
+    /// Set zone LEDs colors, in LED order.
+    pub async fn set_leds(&self, colors: impl AsRef<[Color]>) -> Result<(), OpenRGBError> {
+        self.controller
+            .client
+            .update_zone_leds(self.controller.id, self.id, colors)
+            .await
+    }
+}
+
+/// LED of a [ControllerHandle].
+pub struct LedHandle<'a, S: OpenRGBStream> {
+    controller: &'a ControllerHandle<'a, S>,
+    id: u32,
+}
+
+impl<'a, S: OpenRGBStream + 'static> LedHandle<'a, S> {
+    /// LED ID, index of LED in controller LEDs.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// LED data.
+    pub fn data(&self) -> &'a LED {
+        &self.controller.data.leds[self.id as usize]
+    }
+
+    /// LED name.
+    pub fn name(&self) -> &'a str {
+        &self.data().name
+    }
+
+    /// Set LED color.
+    pub async fn set(&self, color: Color) -> Result<(), OpenRGBError> {
+        let led_id = i32::try_from(self.id)
+            .map_err(|_| InvalidArgument(format!("LED ID {} is too large", self.id)))?;
+        self.controller
+            .client
+            .update_led(self.controller.id, led_id, color)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::time::Duration;
+
+    use tokio::time::timeout;
+    use tokio_test::io::Builder;
+
+    use crate::data::{Color, Controller, DeviceType, Zone, ZoneType, LED};
+    use crate::tests::{controller_reply, setup, OpenRGBMockBuilder};
+    use crate::{OpenRGBError, DEFAULT_PROTOCOL};
+
+    /// Controller with zones of 2 and 3 LEDs.
+    fn controller() -> Controller {
+        let zone = |name: &str, leds_count| Zone {
+            name: name.into(),
+            r#type: ZoneType::Linear,
+            leds_min: 0,
+            leds_max: 10,
+            leds_count,
+            matrix: None,
+            segments: vec![],
+        };
+        Controller {
+            r#type: DeviceType::Cooler,
+            name: "Test Cooler".into(),
+            vendor: "".into(),
+            description: "".into(),
+            version: "".into(),
+            serial: "".into(),
+            location: "".into(),
+            active_mode: 0,
+            modes: vec![],
+            zones: vec![zone("Channel 1", 2), zone("Channel 2", 3)],
+            leds: (0..5)
+                .map(|value| LED {
+                    name: format!("LED {}", value),
+                    value,
+                })
+                .collect(),
+            colors: vec![Color::default(); 5],
+        }
+    }
+
+    fn controller_request(builder: &mut Builder) -> &mut Builder {
+        builder
+            .write(b"ORGB") // magic
+            .write(&3_u32.to_le_bytes()) // device id
+            .write(&1_u32.to_le_bytes()) // packet id
+            .write(&4_u32.to_le_bytes()) // data size
+            .write(&DEFAULT_PROTOCOL.to_le_bytes()) // protocol version
+            .read(&controller_reply(3, controller()))
+    }
+
+    #[tokio::test]
+    async fn test_lookup() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let mut builder = Builder::new();
+        builder.negotiate_default_protocol();
+        let client = controller_request(&mut builder).to_client().await?;
+
+        let controller = client.controller(3).await?;
+        assert_eq!(controller.id(), 3);
+        assert_eq!(controller.name(), "Test Cooler");
+
+        let zone = controller.zone("Channel 2")?;
+        assert_eq!(zone.id(), 1);
+        assert_eq!(zone.offset(), 2);
+        assert_eq!(zone.len(), 3);
+        assert_eq!(zone.led(1)?.name(), "LED 3");
+        assert_eq!(
+            zone.leds().map(|led| led.id()).collect::<Vec<_>>(),
+            [2, 3, 4]
+        );
+        assert!(matches!(zone.led(3), Err(OpenRGBError::InvalidArgument(_))));
+
+        assert_eq!(controller.led("LED 4")?.id(), 4);
+        assert!(matches!(
+            controller.zone("Channel 3"),
+            Err(OpenRGBError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            controller.led("LED 5"),
+            Err(OpenRGBError::InvalidArgument(_))
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_set_zone_and_led() -> Result<(), Box<dyn Error>> {
+        setup()?;
+
+        let mut builder = Builder::new();
+        builder.negotiate_default_protocol();
+        let client = controller_request(&mut builder)
+            .write(b"ORGB") // magic
+            .write(&3_u32.to_le_bytes()) // device id
+            .write(&1051_u32.to_le_bytes()) // packet id
+            .write(&22_u32.to_le_bytes()) // data size
+            .write(&18_u32.to_le_bytes()) // zone data size
+            .write(&1_u32.to_le_bytes()) // zone id
+            .write(&3_u16.to_le_bytes()) // colors count
+            .write(&[1, 2, 3, 0]) // color 1
+            .write(&[1, 2, 3, 0]) // color 2
+            .write(&[1, 2, 3, 0]) // color 3
+            .write(b"ORGB") // magic
+            .write(&3_u32.to_le_bytes()) // device id
+            .write(&1052_u32.to_le_bytes()) // packet id
+            .write(&8_u32.to_le_bytes()) // data size
+            .write(&3_i32.to_le_bytes()) // led id
+            .write(&[4, 5, 6, 0]) // color
+            // round trip, failing if previous writes did not match
+            .write(b"ORGB") // magic
+            .write(&0_u32.to_le_bytes()) // device id
+            .write(&0_u32.to_le_bytes()) // packet id
+            .write(&0_u32.to_le_bytes()) // data size
+            .read(b"ORGB") // magic
+            .read(&0_u32.to_le_bytes()) // device id
+            .read(&0_u32.to_le_bytes()) // packet id
+            .read(&4_u32.to_le_bytes()) // data size
+            .read(&1_u32.to_le_bytes()) // count
+            .to_client()
+            .await?;
+
+        let controller = client.controller(3).await?;
+        controller
+            .zone("Channel 2")?
+            .set_all(Color::new(1, 2, 3))
+            .await?;
+        controller
+            .zone("Channel 2")?
+            .led(1)?
+            .set(Color::new(4, 5, 6))
+            .await?;
+        assert_eq!(
+            timeout(Duration::from_secs(1), client.get_controller_count()).await??,
+            1
+        );
+
+        Ok(())
+    }
+}
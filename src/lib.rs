@@ -23,21 +23,28 @@
 #![warn(missing_docs)]
 #![deny(rustdoc::broken_intra_doc_links)]
 
-#[macro_use]
-extern crate enum_primitive_derive;
-extern crate num_traits;
-
 #[doc(inline)]
 pub use {
+    builder::{OpenRGBBuilder, OPENRGB_HOST_ENV, OPENRGB_PORT_ENV},
     client::{OpenRGB, DEFAULT_ADDR, DEFAULT_PROTOCOL},
+    codec::OpenRGBCodec,
     error::OpenRGBError,
+    event::OpenRGBEvent,
+    handle::{ControllerHandle, LedHandle, ZoneHandle},
     protocol::*,
+    reconnect::ReconnectPolicy,
 };
 
+mod builder;
+mod cache;
 mod client;
+mod codec;
 pub mod data;
 mod error;
+mod event;
+mod handle;
 mod protocol;
+mod reconnect;
 
 #[cfg(test)]
 mod tests;
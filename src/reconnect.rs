@@ -0,0 +1,98 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use crate::OpenRGBError;
+
+/// Automatic reconnection policy of [OpenRGB client](crate::OpenRGB).
+///
+/// The client waits [ReconnectPolicy::initial_delay] before its first connection attempt, then
+/// multiplies that delay by [ReconnectPolicy::multiplier] after each failed attempt, up to
+/// [ReconnectPolicy::max_delay].
+///
+/// See [OpenRGB::connect_to_with_reconnect](crate::OpenRGB::connect_to_with_reconnect).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ReconnectPolicy {
+    /// Delay before first connection attempt.
+    pub initial_delay: Duration,
+
+    /// Maximum delay between connection attempts.
+    pub max_delay: Duration,
+
+    /// Factor applied to delay after each failed connection attempt.
+    pub multiplier: u32,
+
+    /// Maximum number of connection attempts, or `None` to retry forever.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            multiplier: 2,
+            max_attempts: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Delays to wait before each connection attempt.
+    pub(crate) fn delays(&self) -> impl Iterator<Item = Duration> {
+        let max_delay = self.max_delay;
+        let multiplier = self.multiplier;
+        let delays = std::iter::successors(Some(self.initial_delay.min(max_delay)), move |delay| {
+            Some(delay.saturating_mul(multiplier).min(max_delay))
+        });
+        match self.max_attempts {
+            Some(max_attempts) => delays.take(max_attempts as usize),
+            None => delays.take(usize::MAX),
+        }
+    }
+}
+
+/// Opens new streams to server.
+pub(crate) type Connector<S> =
+    Box<dyn Fn() -> Pin<Box<dyn Future<Output = Result<S, OpenRGBError>> + Send>> + Send + Sync>;
+
+/// Reconnection settings of a client.
+pub(crate) struct Reconnect<S> {
+    pub connect: Connector<S>,
+    pub policy: ReconnectPolicy,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::ReconnectPolicy;
+
+    #[test]
+    fn test_delays_001() {
+        let policy = ReconnectPolicy {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(10),
+            multiplier: 3,
+            max_attempts: Some(5),
+        };
+
+        assert_eq!(
+            policy.delays().collect::<Vec<_>>(),
+            vec![
+                Duration::from_secs(1),
+                Duration::from_secs(3),
+                Duration::from_secs(9),
+                Duration::from_secs(10),
+                Duration::from_secs(10),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_delays_002() {
+        let policy = ReconnectPolicy::default();
+
+        assert_eq!(policy.delays().nth(1000), Some(policy.max_delay));
+    }
+}
@@ -1,12 +1,14 @@
 use std::error::Error;
 use std::sync::Once;
 
-use async_trait::async_trait;
 use log::LevelFilter;
+use openrgb_data::{OpenRGBWritableSync, WriteVec};
 use simplelog::{ColorChoice, CombinedLogger, Config, TerminalMode, TermLogger};
 use tokio_test::io::{Builder, Mock};
 
 use crate::{DEFAULT_PROTOCOL, OpenRGB, OpenRGBError};
+use crate::data::Controller;
+use crate::data::PacketId::RequestControllerData;
 use crate::protocol::{OpenRGBReadableStream, OpenRGBStream, OpenRGBWritableStream};
 
 impl OpenRGBReadableStream for Mock {}
@@ -28,14 +30,12 @@ pub fn setup() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-#[async_trait]
 pub trait OpenRGBMockBuilder<S: OpenRGBStream> {
     async fn to_client(&mut self) -> Result<OpenRGB<S>, OpenRGBError>;
     fn negotiate_default_protocol(&mut self) -> &mut Self;
     fn negotiate_protocol(&mut self, protocol: u32) -> &mut Self;
 }
 
-#[async_trait]
 impl OpenRGBMockBuilder<Mock> for Builder {
     async fn to_client(&mut self) -> Result<OpenRGB<Mock>, OpenRGBError> {
         OpenRGB::new(self.build()).await
@@ -63,3 +63,12 @@ impl OpenRGBMockBuilder<Mock> for Builder {
             .read(&protocol.to_le_bytes()) // protocol version
     }
 }
+
+/// Controller data reply packet.
+pub fn controller_reply(controller_id: u32, controller: Controller) -> Vec<u8> {
+    let mut packet = Vec::new();
+    WriteVec::new(&mut packet)
+        .write_packet(DEFAULT_PROTOCOL, controller_id, RequestControllerData, controller)
+        .expect("failed encoding controller");
+    packet
+}